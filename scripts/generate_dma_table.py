@@ -4,6 +4,7 @@ import pprint
 import subprocess
 
 from base import Write
+from generate_remap_table import CFG_TABLE
 
 SCRIPT = os.path.relpath(__file__, os.getcwd()).replace("\\", "/")
 
@@ -17,6 +18,11 @@ def match_filter(filter: str, name: str) -> bool:
 FUNC_TABLE = {
     "TX": "Tx",
     "RX": "Rx",
+    "UP": "TimUp",
+    "CH1": "TimCh1",
+    "CH2": "TimCh2",
+    "CH3": "TimCh3",
+    "CH4": "TimCh4",
 }
 
 
@@ -28,6 +34,11 @@ def write_item(dma: str, ch: str, func: str, w: Write) -> None:
     ch = ch.replace("ch", "C")
     (peri, func) = func.split("_", 1)
     func = FUNC_TABLE.get(func, "")
+    if not func:
+        return
+    cfg = CFG_TABLE.get(peri, "")
+    if cfg:
+        w.write(cfg + "\n")
     w.write(TEMPLATE.format(func=func, peri=peri, dma=dma, ch=ch))
 
 
@@ -81,6 +92,7 @@ def csv_to_code(csv_file: str, show: bool = False) -> None:
     write_table(d, "UART", w)
     write_table(d, "SPI", w)
     write_table(d, "I2C", w)
+    write_table(d, "TIM", w)
 
     w.close()
     subprocess.run(["rustfmt", target_file])
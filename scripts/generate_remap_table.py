@@ -45,6 +45,14 @@ def func_pin_name(filter: str, func: str) -> str:
     return filter[0] + filter[1:].lower() + func[0] + func[1:].lower() + "Pin"
 
 
+# Channels that can also be used as inputs
+INPUT_CHANNELS = ["CH1", "CH2", "CH3", "CH4"]
+
+
+def func_input_pin_name(filter: str, func: str) -> str:
+    return func_pin_name(filter, func).removesuffix("Pin") + "InPin"
+
+
 REG_TEMPLATE = """impl RemapMode<{peri}> for {mode}<{peri}> {{
     fn remap(afio: &mut Afio) {{
         {op}
@@ -92,16 +100,22 @@ def write_binder_type(d: dict, filter: str, w: Write) -> None:
         name = func_pin_name(filter, func)
         w.write(f"pub trait {name}<REMAP> {{}}")
         w.write(f"impl<T> {name}<T> for NonePin {{}}")
+        if func in INPUT_CHANNELS:
+            name = func_input_pin_name(filter, func)
+            w.write(f"pub trait {name}<REMAP> {{}}")
+            w.write(f"impl<T> {name}<T> for NonePin {{}}")
     w.write("\n")
 
 
 IMPL_TEMPLATE_LIST = [
     (
-        ["TX", "CK", "CH1", "CH2", "CH3", "CH4"],
+        ["TX", "CK", "CH1", "CH2", "CH3", "CH4", "CH1N", "CH2N", "CH3N"],
         "impl {func}<{mode}<{peri}>> for {pin}<Alternate<PushPull>>",
     ),
     (["RX"], "impl<PULL: UpMode> {func}<{mode}<{peri}>> for {pin}<Input<PULL>>"),
+    (["BKIN", "ETR"], "impl<PULL> {func}<{mode}<{peri}>> for {pin}<Input<PULL>>"),
 ]
+INPUT_IMPL_TEMPLATE = "impl<PULL> {func}<{mode}<{peri}>> for {pin}<Input<PULL>>"
 
 
 def get_impl_template(func: str) -> str:
@@ -112,10 +126,10 @@ def get_impl_template(func: str) -> str:
 
 
 def write_item(filter: str, peri: str, mode: str, pins: dict[str, str], w: Write) -> None:
+    cfg = CFG_TABLE.get(peri, "")
     for pin_func, pin in sorted(pins.items()):
         impl = get_impl_template(pin_func)
         if impl:
-            cfg = CFG_TABLE.get(peri, "")
             if cfg:
                 w.write(cfg)
 
@@ -123,6 +137,13 @@ def write_item(filter: str, peri: str, mode: str, pins: dict[str, str], w: Write
             w.write(impl.format(func=func, mode=mode, peri=peri, pin=pin))
             w.write("{}")
 
+        if pin_func in INPUT_CHANNELS:
+            if cfg:
+                w.write(cfg)
+            func = func_input_pin_name(filter, pin_func)
+            w.write(INPUT_IMPL_TEMPLATE.format(func=func, mode=mode, peri=peri, pin=pin))
+            w.write("{}")
+
 
 def write_table(d: dict, filter: str, csv_file: str, target_file: str) -> None:
     with open(target_file, "r", encoding="utf-8") as f:
@@ -0,0 +1,83 @@
+import csv
+import os
+import pprint
+import subprocess
+
+from base import Write
+from generate_remap_table import CFG_TABLE
+
+SCRIPT = os.path.relpath(__file__, os.getcwd()).replace("\\", "/")
+
+TEMPLATE = """impl InternalTrigger<pac::{master}> for pac::{slave} {{
+    const SOURCE: TriggerSource = TriggerSource::Itr{itr};
+}}
+"""
+
+
+def cfg_condition(peri: str) -> str:
+    cfg = CFG_TABLE.get(peri, "")
+    return cfg.removeprefix("#[cfg(").removesuffix(")]")
+
+
+def variant_condition(variant: str) -> str:
+    if variant.startswith("!"):
+        return f'not(feature = "{variant[1:]}")'
+    if variant:
+        return f'feature = "{variant}"'
+    return ""
+
+
+def write_item(variant: str, slave: str, itr: int, master: str, w: Write) -> None:
+    conditions = [
+        c
+        for c in (variant_condition(variant), cfg_condition(slave), cfg_condition(master))
+        if c
+    ]
+    if len(conditions) == 1:
+        w.write(f"#[cfg({conditions[0]})]\n")
+    elif conditions:
+        w.write(f"#[cfg(all({', '.join(conditions)}))]\n")
+    w.write(TEMPLATE.format(master=master, slave=slave, itr=itr))
+
+
+def parse_trigger_info(row: list[str], ret_l: list) -> None:
+    variant = row[0]
+    slave = row[1]
+    for itr, master in enumerate(row[2:6]):
+        if master:
+            ret_l.append((variant, slave, itr, master))
+
+
+def csv_to_code(csv_file: str, show: bool = False) -> None:
+    print(csv_file)
+    items: list = []
+    with open(csv_file, newline="", encoding="utf-8") as f:
+        reader = csv.reader(f, delimiter=",", quotechar='"')
+        for row in reader:
+            if row[1]:
+                parse_trigger_info(row, items)
+
+    if show:
+        pprint.pprint(items)
+
+    target_file = "src/timer/trigger.rs"
+    with open(target_file, "r", encoding="utf-8") as f:
+        code = f.read()
+        i = code.find("// table") + len("// table")
+        before = code[:i]
+
+    w = Write(target_file)
+    w.write(before)
+    w.write("\n// Do NOT manually modify the code.\n")
+    w.write(
+        f"// It's generated by {SCRIPT} from {csv_file}\n\n",
+    )
+    for item in items:
+        write_item(*item, w)
+
+    w.close()
+    subprocess.run(["rustfmt", target_file])
+
+
+if __name__ == "__main__":
+    csv_to_code("scripts/table/stm32f1_timer_trigger.csv")
@@ -12,141 +12,311 @@ pub trait TimBkinPin<REMAP> {}
 impl<T> TimBkinPin<T> for NonePin {}
 pub trait TimCh1Pin<REMAP> {}
 impl<T> TimCh1Pin<T> for NonePin {}
+pub trait TimCh1InPin<REMAP> {}
+impl<T> TimCh1InPin<T> for NonePin {}
 pub trait TimCh1nPin<REMAP> {}
 impl<T> TimCh1nPin<T> for NonePin {}
 pub trait TimCh2Pin<REMAP> {}
 impl<T> TimCh2Pin<T> for NonePin {}
+pub trait TimCh2InPin<REMAP> {}
+impl<T> TimCh2InPin<T> for NonePin {}
 pub trait TimCh2nPin<REMAP> {}
 impl<T> TimCh2nPin<T> for NonePin {}
 pub trait TimCh3Pin<REMAP> {}
 impl<T> TimCh3Pin<T> for NonePin {}
+pub trait TimCh3InPin<REMAP> {}
+impl<T> TimCh3InPin<T> for NonePin {}
 pub trait TimCh3nPin<REMAP> {}
 impl<T> TimCh3nPin<T> for NonePin {}
 pub trait TimCh4Pin<REMAP> {}
 impl<T> TimCh4Pin<T> for NonePin {}
+pub trait TimCh4InPin<REMAP> {}
+impl<T> TimCh4InPin<T> for NonePin {}
 pub trait TimEtrPin<REMAP> {}
 impl<T> TimEtrPin<T> for NonePin {}
 
 // Bind pins ---------------------
 
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimBkinPin<RemapDefault<TIM1>> for PB12<Input<PULL>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh1Pin<RemapDefault<TIM1>> for PA8<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM1>> for PA8<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh1nPin<RemapDefault<TIM1>> for PB13<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh2Pin<RemapDefault<TIM1>> for PA9<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh2InPin<RemapDefault<TIM1>> for PA9<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh2nPin<RemapDefault<TIM1>> for PB14<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh3Pin<RemapDefault<TIM1>> for PA10<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh3InPin<RemapDefault<TIM1>> for PA10<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh3nPin<RemapDefault<TIM1>> for PB15<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh4Pin<RemapDefault<TIM1>> for PA11<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh4InPin<RemapDefault<TIM1>> for PA11<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimEtrPin<RemapDefault<TIM1>> for PA12<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimBkinPin<RemapFull<TIM1>> for PE15<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh1Pin<RemapFull<TIM1>> for PE9<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh1InPin<RemapFull<TIM1>> for PE9<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh1nPin<RemapFull<TIM1>> for PE8<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh2Pin<RemapFull<TIM1>> for PE11<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh2InPin<RemapFull<TIM1>> for PE11<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh2nPin<RemapFull<TIM1>> for PE10<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh3Pin<RemapFull<TIM1>> for PE13<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh3InPin<RemapFull<TIM1>> for PE13<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh3nPin<RemapFull<TIM1>> for PE12<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh4Pin<RemapFull<TIM1>> for PE14<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh4InPin<RemapFull<TIM1>> for PE14<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimEtrPin<RemapFull<TIM1>> for PE7<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimBkinPin<RemapPartial1<TIM1>> for PA6<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh1Pin<RemapPartial1<TIM1>> for PA8<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh1InPin<RemapPartial1<TIM1>> for PA8<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh1nPin<RemapPartial1<TIM1>> for PA7<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh2Pin<RemapPartial1<TIM1>> for PA9<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh2InPin<RemapPartial1<TIM1>> for PA9<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh2nPin<RemapPartial1<TIM1>> for PB0<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh3Pin<RemapPartial1<TIM1>> for PA10<Alternate<PushPull>> {}
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh3InPin<RemapPartial1<TIM1>> for PA10<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl TimCh3nPin<RemapPartial1<TIM1>> for PB1<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 impl TimCh4Pin<RemapPartial1<TIM1>> for PA11<Alternate<PushPull>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimCh4InPin<RemapPartial1<TIM1>> for PA11<Input<PULL>> {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl<PULL> TimEtrPin<RemapPartial1<TIM1>> for PA12<Input<PULL>> {}
 #[cfg(feature = "xl")]
 impl TimCh1Pin<RemapDefault<TIM10>> for PB8<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapDefault<TIM10>> for PB8<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh1Pin<RemapFull<TIM10>> for PF6<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapFull<TIM10>> for PF6<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh1Pin<RemapDefault<TIM11>> for PB9<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapDefault<TIM11>> for PB9<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh1Pin<RemapFull<TIM11>> for PF7<Alternate<PushPull>> {}
+#[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapFull<TIM11>> for PF7<Input<PULL>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh1Pin<RemapDefault<TIM12>> for PB14<Alternate<PushPull>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM12>> for PB14<Input<PULL>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh2Pin<RemapDefault<TIM12>> for PB15<Alternate<PushPull>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh2InPin<RemapDefault<TIM12>> for PB15<Input<PULL>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh1Pin<RemapDefault<TIM13>> for PA6<Alternate<PushPull>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM13>> for PA6<Input<PULL>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh1Pin<RemapFull<TIM13>> for PF8<Alternate<PushPull>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh1InPin<RemapFull<TIM13>> for PF8<Input<PULL>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh1Pin<RemapDefault<TIM14>> for PA7<Alternate<PushPull>> {}
 #[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM14>> for PA7<Input<PULL>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
 impl TimCh1Pin<RemapFull<TIM14>> for PF9<Alternate<PushPull>> {}
+#[cfg(any(feature = "xl", all(feature = "stm32f100", feature = "high",)))]
+impl<PULL> TimCh1InPin<RemapFull<TIM14>> for PF9<Input<PULL>> {}
 impl TimCh1Pin<RemapDefault<TIM2>> for PA0<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapDefault<TIM2>> for PA0<Input<PULL>> {}
 impl TimCh2Pin<RemapDefault<TIM2>> for PA1<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapDefault<TIM2>> for PA1<Input<PULL>> {}
 impl TimCh3Pin<RemapDefault<TIM2>> for PA2<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapDefault<TIM2>> for PA2<Input<PULL>> {}
 impl TimCh4Pin<RemapDefault<TIM2>> for PA3<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapDefault<TIM2>> for PA3<Input<PULL>> {}
+impl<PULL> TimEtrPin<RemapDefault<TIM2>> for PA0<Input<PULL>> {}
 impl TimCh1Pin<RemapFull<TIM2>> for PA15<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapFull<TIM2>> for PA15<Input<PULL>> {}
 impl TimCh2Pin<RemapFull<TIM2>> for PB3<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapFull<TIM2>> for PB3<Input<PULL>> {}
 impl TimCh3Pin<RemapFull<TIM2>> for PB10<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapFull<TIM2>> for PB10<Input<PULL>> {}
 impl TimCh4Pin<RemapFull<TIM2>> for PB11<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapFull<TIM2>> for PB11<Input<PULL>> {}
+impl<PULL> TimEtrPin<RemapFull<TIM2>> for PA15<Input<PULL>> {}
 impl TimCh1Pin<RemapPartial1<TIM2>> for PA15<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapPartial1<TIM2>> for PA15<Input<PULL>> {}
 impl TimCh2Pin<RemapPartial1<TIM2>> for PB3<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapPartial1<TIM2>> for PB3<Input<PULL>> {}
 impl TimCh3Pin<RemapPartial1<TIM2>> for PA2<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapPartial1<TIM2>> for PA2<Input<PULL>> {}
 impl TimCh4Pin<RemapPartial1<TIM2>> for PA3<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapPartial1<TIM2>> for PA3<Input<PULL>> {}
+impl<PULL> TimEtrPin<RemapPartial1<TIM2>> for PA15<Input<PULL>> {}
 impl TimCh1Pin<RemapPartial2<TIM2>> for PA0<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapPartial2<TIM2>> for PA0<Input<PULL>> {}
 impl TimCh2Pin<RemapPartial2<TIM2>> for PA1<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapPartial2<TIM2>> for PA1<Input<PULL>> {}
 impl TimCh3Pin<RemapPartial2<TIM2>> for PB10<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapPartial2<TIM2>> for PB10<Input<PULL>> {}
 impl TimCh4Pin<RemapPartial2<TIM2>> for PB11<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapPartial2<TIM2>> for PB11<Input<PULL>> {}
+impl<PULL> TimEtrPin<RemapPartial2<TIM2>> for PA0<Input<PULL>> {}
 impl TimCh1Pin<RemapDefault<TIM3>> for PA6<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapDefault<TIM3>> for PA6<Input<PULL>> {}
 impl TimCh2Pin<RemapDefault<TIM3>> for PA7<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapDefault<TIM3>> for PA7<Input<PULL>> {}
 impl TimCh3Pin<RemapDefault<TIM3>> for PB0<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapDefault<TIM3>> for PB0<Input<PULL>> {}
 impl TimCh4Pin<RemapDefault<TIM3>> for PB1<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapDefault<TIM3>> for PB1<Input<PULL>> {}
 impl TimCh1Pin<RemapFull<TIM3>> for PC6<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapFull<TIM3>> for PC6<Input<PULL>> {}
 impl TimCh2Pin<RemapFull<TIM3>> for PC7<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapFull<TIM3>> for PC7<Input<PULL>> {}
 impl TimCh3Pin<RemapFull<TIM3>> for PC8<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapFull<TIM3>> for PC8<Input<PULL>> {}
 impl TimCh4Pin<RemapFull<TIM3>> for PC9<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapFull<TIM3>> for PC9<Input<PULL>> {}
 impl TimCh1Pin<RemapPartial1<TIM3>> for PB4<Alternate<PushPull>> {}
+impl<PULL> TimCh1InPin<RemapPartial1<TIM3>> for PB4<Input<PULL>> {}
 impl TimCh2Pin<RemapPartial1<TIM3>> for PB5<Alternate<PushPull>> {}
+impl<PULL> TimCh2InPin<RemapPartial1<TIM3>> for PB5<Input<PULL>> {}
 impl TimCh3Pin<RemapPartial1<TIM3>> for PB0<Alternate<PushPull>> {}
+impl<PULL> TimCh3InPin<RemapPartial1<TIM3>> for PB0<Input<PULL>> {}
 impl TimCh4Pin<RemapPartial1<TIM3>> for PB1<Alternate<PushPull>> {}
+impl<PULL> TimCh4InPin<RemapPartial1<TIM3>> for PB1<Input<PULL>> {}
 #[cfg(feature = "medium")]
 impl TimCh1Pin<RemapDefault<TIM4>> for PB6<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh1InPin<RemapDefault<TIM4>> for PB6<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh2Pin<RemapDefault<TIM4>> for PB7<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh2InPin<RemapDefault<TIM4>> for PB7<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh3Pin<RemapDefault<TIM4>> for PB8<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh3InPin<RemapDefault<TIM4>> for PB8<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh4Pin<RemapDefault<TIM4>> for PB9<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh4InPin<RemapDefault<TIM4>> for PB9<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh1Pin<RemapFull<TIM4>> for PD12<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh1InPin<RemapFull<TIM4>> for PD12<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh2Pin<RemapFull<TIM4>> for PD13<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh2InPin<RemapFull<TIM4>> for PD13<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh3Pin<RemapFull<TIM4>> for PD14<Alternate<PushPull>> {}
 #[cfg(feature = "medium")]
+impl<PULL> TimCh3InPin<RemapFull<TIM4>> for PD14<Input<PULL>> {}
+#[cfg(feature = "medium")]
 impl TimCh4Pin<RemapFull<TIM4>> for PD15<Alternate<PushPull>> {}
+#[cfg(feature = "medium")]
+impl<PULL> TimCh4InPin<RemapFull<TIM4>> for PD15<Input<PULL>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh1Pin<RemapDefault<TIM5>> for PA0<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM5>> for PA0<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh2Pin<RemapDefault<TIM5>> for PA1<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh2InPin<RemapDefault<TIM5>> for PA1<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh3Pin<RemapDefault<TIM5>> for PA2<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh3InPin<RemapDefault<TIM5>> for PA2<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh4Pin<RemapDefault<TIM5>> for PA3<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh4InPin<RemapDefault<TIM5>> for PA3<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh1Pin<RemapFull<TIM5>> for PA0<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh1InPin<RemapFull<TIM5>> for PA0<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh2Pin<RemapFull<TIM5>> for PA1<Alternate<PushPull>> {}
 #[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh2InPin<RemapFull<TIM5>> for PA1<Input<PULL>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
 impl TimCh3Pin<RemapFull<TIM5>> for PA2<Alternate<PushPull>> {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl<PULL> TimCh3InPin<RemapFull<TIM5>> for PA2<Input<PULL>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimBkinPin<RemapDefault<TIM8>> for PA6<Input<PULL>> {}
 #[cfg(all(feature = "stm32f103", feature = "high"))]
 impl TimCh1Pin<RemapDefault<TIM8>> for PC6<Alternate<PushPull>> {}
 #[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimCh1InPin<RemapDefault<TIM8>> for PC6<Input<PULL>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl TimCh1nPin<RemapDefault<TIM8>> for PA8<Alternate<PushPull>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
 impl TimCh2Pin<RemapDefault<TIM8>> for PC7<Alternate<PushPull>> {}
 #[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimCh2InPin<RemapDefault<TIM8>> for PC7<Input<PULL>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl TimCh2nPin<RemapDefault<TIM8>> for PB0<Alternate<PushPull>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
 impl TimCh3Pin<RemapDefault<TIM8>> for PC8<Alternate<PushPull>> {}
 #[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimCh3InPin<RemapDefault<TIM8>> for PC8<Input<PULL>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl TimCh3nPin<RemapDefault<TIM8>> for PB1<Alternate<PushPull>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
 impl TimCh4Pin<RemapDefault<TIM8>> for PC9<Alternate<PushPull>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimCh4InPin<RemapDefault<TIM8>> for PC9<Input<PULL>> {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl<PULL> TimEtrPin<RemapDefault<TIM8>> for PA0<Input<PULL>> {}
 #[cfg(feature = "xl")]
 impl TimCh1Pin<RemapDefault<TIM9>> for PA2<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapDefault<TIM9>> for PA2<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh2Pin<RemapDefault<TIM9>> for PA3<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh2InPin<RemapDefault<TIM9>> for PA3<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh1Pin<RemapFull<TIM9>> for PE5<Alternate<PushPull>> {}
 #[cfg(feature = "xl")]
+impl<PULL> TimCh1InPin<RemapFull<TIM9>> for PE5<Input<PULL>> {}
+#[cfg(feature = "xl")]
 impl TimCh2Pin<RemapFull<TIM9>> for PE6<Alternate<PushPull>> {}
+#[cfg(feature = "xl")]
+impl<PULL> TimCh2InPin<RemapFull<TIM9>> for PE6<Input<PULL>> {}
 
 // Register operations ------------
 
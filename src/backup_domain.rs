@@ -10,10 +10,24 @@
   Write access to the backup domain is enabled in RCC using the `rcc::Rcc::BKP::constrain()`
   function.
 
-  Only the RTC functionality is currently implemented.
+  [BackupReg] addresses the data registers. DR11 to DR42 only exist on high-density and
+  connectivity line devices, so using them on other devices doesn't compile.
+
+  [BackupStore] keeps a few checksummed key/value pairs in the data registers, e.g. a boot
+  counter or a bootloader flag.
+
+  The TAMPER pin (PC13) can be used either as the tamper detection input, or to output the RTC
+  calibration clock or the alarm/second pulse.
 */
 
-use crate::pac::BKP;
+use crate::{Steal, common::crc16::crc16_update, gpio, pac::BKP};
+use core::ops::Range;
+
+/// One calibration step slows the RTC clock down by 1/2^20 (about 0.954 ppm).
+const CAL_DIVISOR: u32 = 1 << 20;
+const CAL_MAX: u8 = 0x7F;
+/// Number of the DR1 to DR10 registers
+const DR_LOW_COUNT: usize = 10;
 
 /**
   The existence of this struct indicates that writing to the the backup
@@ -36,6 +50,27 @@ macro_rules! read_drx {
 }
 
 impl BackupDomain {
+    /// Read a 16-bit value from a data register.
+    pub fn read(&self, reg: BackupReg) -> u16 {
+        let idx = reg.index();
+        #[cfg(any(feature = "high", feature = "connectivity"))]
+        if idx >= DR_LOW_COUNT {
+            return read_drx!(self, bkp_dr, idx - DR_LOW_COUNT);
+        }
+        read_drx!(self, dr, idx)
+    }
+
+    /// Write a 16-bit value to a data register.
+    pub fn write(&mut self, reg: BackupReg, data: u16) {
+        let idx = reg.index();
+        #[cfg(any(feature = "high", feature = "connectivity"))]
+        if idx >= DR_LOW_COUNT {
+            write_drx!(self, bkp_dr, idx - DR_LOW_COUNT, data);
+            return;
+        }
+        write_drx!(self, dr, idx, data);
+    }
+
     /// Read a 16-bit value from one of the DR1 to DR10 registers part of the
     /// Backup Data Register. The register argument is a zero based index to the
     /// DRx registers: 0 is DR1, up to 9 for DR10. Providing a number above 9
@@ -71,4 +106,309 @@ impl BackupDomain {
     pub fn write_data_register_high(&self, register: usize, data: u16) {
         write_drx!(self, bkp_dr, register, data);
     }
+
+    /// Enable tamper detection on the TAMPER pin (PC13).
+    ///
+    /// When the active level is detected, all the data registers are erased by hardware and the
+    /// tamper event flag is set. Note that the pin can't output signals when it is used here.
+    pub fn enable_tamper<MODE>(&mut self, _pin: gpio::PC13<MODE>, level: TamperLevel) {
+        self.disable_tamper();
+        self._regs
+            .cr()
+            .modify(|_, w| w.tpal().bit(level == TamperLevel::Low));
+        self._regs.cr().modify(|_, w| w.tpe().set_bit());
+    }
+
+    pub fn disable_tamper(&mut self) {
+        self._regs.cr().modify(|_, w| w.tpe().clear_bit());
+    }
+
+    /// Enable the tamper interrupt.
+    ///
+    /// Note, you will also have to enable the TAMPER interrupt in the NVIC.
+    pub fn listen_tamper(&mut self) {
+        self._regs.csr().modify(|_, w| w.tpie().set_bit());
+    }
+
+    pub fn unlisten_tamper(&mut self) {
+        self._regs.csr().modify(|_, w| w.tpie().clear_bit());
+    }
+
+    /// Whether a tamper event has been detected. It's kept until it's cleared or a power reset.
+    #[inline]
+    pub fn is_tamper_event(&self) -> bool {
+        self._regs.csr().read().tef().bit_is_set()
+    }
+
+    /// Clear the tamper event flag. The data registers can't be written while it is set.
+    #[inline]
+    pub fn clear_tamper_event(&mut self) {
+        self._regs.csr().modify(|_, w| w.cte().set_bit());
+    }
+
+    pub fn get_tamper_interrupt_handler(&self) -> TamperInterrupt {
+        TamperInterrupt {
+            bkp: unsafe { self._regs.steal() },
+        }
+    }
+
+    /// Slow down the RTC clock by `ppm` (0 to 121), to compensate a fast LSE crystal.
+    /// The resolution is about 0.954 ppm.
+    pub fn set_rtc_calibration(&mut self, ppm: u8) {
+        let cal = (ppm as u32 * CAL_DIVISOR + 500_000) / 1_000_000;
+        assert!(cal <= CAL_MAX as u32, "RTC calibration is out of range");
+        self._regs
+            .rtccr()
+            .modify(|_, w| unsafe { w.cal().bits(cal as u8) });
+    }
+
+    /// Returns the current calibration value in ppm.
+    pub fn get_rtc_calibration(&self) -> u8 {
+        let cal = self._regs.rtccr().read().cal().bits() as u32;
+        ((cal * 1_000_000 + CAL_DIVISOR / 2) / CAL_DIVISOR) as u8
+    }
+
+    /// Output a signal on the TAMPER pin (PC13). Tamper detection must be disabled.
+    ///
+    /// [TamperPinOutput::CalibrationClock] can be measured to trim the RTC clock with
+    /// [set_rtc_calibration](Self::set_rtc_calibration).
+    pub fn enable_tamper_pin_output<MODE>(
+        &mut self,
+        _pin: gpio::PC13<MODE>,
+        output: TamperPinOutput,
+    ) {
+        assert!(
+            self._regs.cr().read().tpe().bit_is_clear(),
+            "TAMPER pin is used by tamper detection"
+        );
+        self._regs.rtccr().modify(|_, w| match output {
+            TamperPinOutput::CalibrationClock => w.asoe().clear_bit().cco().set_bit(),
+            TamperPinOutput::AlarmPulse => w.cco().clear_bit().asos().alarm().asoe().set_bit(),
+            TamperPinOutput::SecondPulse => w.cco().clear_bit().asos().second().asoe().set_bit(),
+        });
+    }
+
+    pub fn disable_tamper_pin_output(&mut self) {
+        self._regs
+            .rtccr()
+            .modify(|_, w| w.cco().clear_bit().asoe().clear_bit());
+    }
+}
+
+/// Can be used in the TAMPER interrupt callback.
+pub struct TamperInterrupt {
+    bkp: BKP,
+}
+
+impl TamperInterrupt {
+    /// Check and clear the tamper interrupt and event flags.
+    #[inline]
+    pub fn is_interrupted(&mut self) -> bool {
+        if self.bkp.csr().read().tif().bit_is_set() {
+            self.bkp
+                .csr()
+                .modify(|_, w| w.cti().set_bit().cte().set_bit());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A small key/value store in a range of data registers.
+///
+/// Each entry uses two registers, the key and the value, and the last register of the range
+/// keeps a checksum of all the others. The content is treated as empty when the checksum doesn't
+/// match, e.g. after a power loss of the backup domain or a tamper event.
+///
+/// ```rust
+/// const BOOT_COUNT: u16 = 1;
+/// let mut store = BackupStore::new(&mut bkp);
+/// let count = store.get(BOOT_COUNT).unwrap_or(0);
+/// store.set(BOOT_COUNT, count + 1).unwrap();
+/// ```
+pub struct BackupStore<'a> {
+    bkp: &'a mut BackupDomain,
+    regs: Range<usize>,
+}
+
+impl<'a> BackupStore<'a> {
+    /// Use all the data registers.
+    pub fn new(bkp: &'a mut BackupDomain) -> Self {
+        Self {
+            bkp,
+            regs: 0..BackupReg::COUNT,
+        }
+    }
+
+    /// Use the registers from `first` to `last` inclusive, so that the others can be used
+    /// directly. At least 3 registers are needed.
+    pub fn with_range(bkp: &'a mut BackupDomain, first: BackupReg, last: BackupReg) -> Self {
+        assert!(
+            last.index() >= first.index() + 2,
+            "backup store needs at least 3 registers"
+        );
+        Self {
+            bkp,
+            regs: first.index()..last.index() + 1,
+        }
+    }
+
+    /// Maximum number of entries
+    pub fn capacity(&self) -> usize {
+        (self.regs.len() - 1) / 2
+    }
+
+    /// Whether the checksum matches.
+    pub fn is_valid(&self) -> bool {
+        self.read_at(self.regs.len() - 1) == self.checksum()
+    }
+
+    /// Remove all the entries.
+    pub fn clear(&mut self) {
+        for i in 0..self.regs.len() - 1 {
+            self.write_at(i, 0);
+        }
+        self.update_checksum();
+    }
+
+    pub fn get(&self, key: u16) -> Option<u16> {
+        if !self.is_valid() {
+            return None;
+        }
+        self.find(key).map(|slot| self.read_at(slot * 2 + 1))
+    }
+
+    /// Insert or update an entry. The key `0` is reserved.
+    pub fn set(&mut self, key: u16, value: u16) -> Result<(), StoreError> {
+        if key == 0 {
+            return Err(StoreError::InvalidKey);
+        }
+        if !self.is_valid() {
+            self.clear();
+        }
+        let slot = self
+            .find(key)
+            .or_else(|| self.find(0))
+            .ok_or(StoreError::Full)?;
+        self.write_at(slot * 2, key);
+        self.write_at(slot * 2 + 1, value);
+        self.update_checksum();
+        Ok(())
+    }
+
+    /// Remove an entry, returning its value.
+    pub fn remove(&mut self, key: u16) -> Option<u16> {
+        let value = self.get(key)?;
+        if let Some(slot) = self.find(key) {
+            self.write_at(slot * 2, 0);
+            self.write_at(slot * 2 + 1, 0);
+            self.update_checksum();
+        }
+        Some(value)
+    }
+
+    fn find(&self, key: u16) -> Option<usize> {
+        (0..self.capacity()).find(|&slot| self.read_at(slot * 2) == key)
+    }
+
+    fn update_checksum(&mut self) {
+        let sum = self.checksum();
+        self.write_at(self.regs.len() - 1, sum);
+    }
+
+    fn checksum(&self) -> u16 {
+        (0..self.regs.len() - 1).fold(0xFFFF, |crc, i| {
+            crc16_update(crc, &self.read_at(i).to_be_bytes())
+        })
+    }
+
+    #[inline]
+    fn read_at(&self, i: usize) -> u16 {
+        self.bkp
+            .read(BackupReg::from_index(self.regs.start + i).unwrap())
+    }
+
+    #[inline]
+    fn write_at(&mut self, i: usize, data: u16) {
+        let reg = BackupReg::from_index(self.regs.start + i).unwrap();
+        self.bkp.write(reg, data);
+    }
+}
+
+// Enumerate ------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreError {
+    /// The key `0` is reserved for empty entries.
+    InvalidKey,
+    /// There's no free entry.
+    Full,
+}
+
+macro_rules! backup_reg {
+    ($($reg:ident: $idx:literal),+; $($reg_h:ident: $idx_h:literal),+) => {
+        /// Data register index
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(u8)]
+        pub enum BackupReg {
+            $($reg = $idx,)+
+            $(
+                #[cfg(any(feature = "high", feature = "connectivity"))]
+                $reg_h = $idx_h,
+            )+
+        }
+    };
+}
+
+backup_reg!(
+    Dr1: 0, Dr2: 1, Dr3: 2, Dr4: 3, Dr5: 4, Dr6: 5, Dr7: 6, Dr8: 7, Dr9: 8, Dr10: 9;
+    Dr11: 10, Dr12: 11, Dr13: 12, Dr14: 13, Dr15: 14, Dr16: 15, Dr17: 16, Dr18: 17,
+    Dr19: 18, Dr20: 19, Dr21: 20, Dr22: 21, Dr23: 22, Dr24: 23, Dr25: 24, Dr26: 25,
+    Dr27: 26, Dr28: 27, Dr29: 28, Dr30: 29, Dr31: 30, Dr32: 31, Dr33: 32, Dr34: 33,
+    Dr35: 34, Dr36: 35, Dr37: 36, Dr38: 37, Dr39: 38, Dr40: 39, Dr41: 40, Dr42: 41
+);
+
+impl BackupReg {
+    /// Number of data registers on this device
+    #[cfg(any(feature = "high", feature = "connectivity"))]
+    pub const COUNT: usize = 42;
+    /// Number of data registers on this device
+    #[cfg(not(any(feature = "high", feature = "connectivity")))]
+    pub const COUNT: usize = DR_LOW_COUNT;
+
+    /// Zero based index, 0 is DR1.
+    #[inline]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns `None` if the register doesn't exist on this device.
+    #[inline]
+    pub const fn from_index(idx: usize) -> Option<Self> {
+        if idx < Self::COUNT {
+            // Safety: the variants are contiguous from 0 to COUNT - 1
+            Some(unsafe { core::mem::transmute::<u8, Self>(idx as u8) })
+        } else {
+            None
+        }
+    }
+}
+
+/// Active level of the TAMPER pin
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TamperLevel {
+    High,
+    Low,
+}
+
+/// Signal output on the TAMPER pin
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TamperPinOutput {
+    /// RTCCLK divided by 64
+    CalibrationClock,
+    /// RTC alarm pulse
+    AlarmPulse,
+    /// RTC second pulse
+    SecondPulse,
 }
@@ -0,0 +1,15 @@
+/// CRC-16 with the CCITT polynomial, MSB first. Start with `0xFFFF` for CRC-16/CCITT-FALSE, or
+/// with `0` for XMODEM.
+pub(crate) fn crc16_update(mut crc: u16, data: &[u8]) -> u16 {
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+/// Maximum transfer length of a DMA channel
+const MAX_LEN: usize = u16::MAX as usize;
+
+/// Copy or fill memory by a DMA channel in memory-to-memory mode.
+///
+/// Transfers longer than 65535 elements are split into several chunks, the next chunk is started
+/// by [poll](Self::poll).
+pub struct DmaMemcpy<CH> {
+    ch: CH,
+    job: Option<Job>,
+}
+
+struct Job {
+    src: usize,
+    src_increase: bool,
+    dst: usize,
+    /// Element size in bytes
+    size: usize,
+    /// Elements not started yet
+    left: usize,
+}
+
+impl<CH> DmaMemcpy<CH>
+where
+    CH: DmaChannel,
+{
+    pub fn new(ch: CH) -> Self {
+        Self { ch, job: None }
+    }
+
+    pub fn release(mut self) -> CH {
+        self.cancel();
+        self.ch
+    }
+
+    /// Copy `src` into `dst` and wait until it's done.
+    pub fn copy<T: Sized + Copy>(&mut self, src: &[T], dst: &mut [T]) -> Result<(), DmaError> {
+        unsafe { self.start_copy(src, dst) };
+        nb::block!(self.poll())
+    }
+
+    /// Fill `dst` with `value` and wait until it's done.
+    pub fn fill<T: Sized + Copy>(&mut self, value: T, dst: &mut [T]) -> Result<(), DmaError> {
+        unsafe { self.start_fill(&value, dst) };
+        nb::block!(self.poll())
+    }
+
+    /// Start copying `src` into `dst`, then call [poll](Self::poll) until it's done.
+    ///
+    /// # Safety
+    ///
+    /// Both buffers must stay valid and not be accessed until the transfer is done or canceled.
+    pub unsafe fn start_copy<T: Sized + Copy>(&mut self, src: &[T], dst: &mut [T]) {
+        assert_eq!(src.len(), dst.len());
+        self.start::<T>(
+            src.as_ptr() as usize,
+            true,
+            dst.as_mut_ptr() as usize,
+            dst.len(),
+        );
+    }
+
+    /// Start filling `dst` with `*value`, then call [poll](Self::poll) until it's done.
+    ///
+    /// # Safety
+    ///
+    /// Both `value` and `dst` must stay valid, and `dst` must not be accessed until the transfer
+    /// is done or canceled.
+    pub unsafe fn start_fill<T: Sized + Copy>(&mut self, value: &T, dst: &mut [T]) {
+        let src = value as *const T as usize;
+        self.start::<T>(src, false, dst.as_mut_ptr() as usize, dst.len());
+    }
+
+    /// Returns `Ok` when the whole transfer is done. A transfer error cancels the transfer.
+    pub fn poll(&mut self) -> nb::Result<(), DmaError> {
+        if self.ch.in_progress() {
+            return Err(nb::Error::WouldBlock);
+        }
+        if self.ch.is_interrupted(DmaEvent::TransferError) {
+            self.cancel();
+            return Err(nb::Error::Other(DmaError::TransferError));
+        }
+        match self.job.as_ref() {
+            Some(job) if job.left > 0 => {
+                self.load_next();
+                Err(nb::Error::WouldBlock)
+            }
+            _ => {
+                self.ch.stop();
+                self.job = None;
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    pub fn in_progress(&self) -> bool {
+        self.job.is_some()
+    }
+
+    pub fn cancel(&mut self) {
+        self.ch.stop();
+        self.job = None;
+    }
+
+    fn start<T: Sized + Copy>(&mut self, src: usize, src_increase: bool, dst: usize, len: usize) {
+        let size = core::mem::size_of::<T>();
+        assert!(
+            matches!(size, 1 | 2 | 4),
+            "element size must be 1, 2 or 4 bytes"
+        );
+        // The DMA accesses are as wide as the element, `T` like `[u8; 4]` may be unaligned.
+        assert!(
+            src.is_multiple_of(size) && dst.is_multiple_of(size),
+            "buffers must be aligned to the element size"
+        );
+        self.cancel();
+        self.job = Some(Job {
+            src,
+            src_increase,
+            dst,
+            size,
+            left: len,
+        });
+        self.load_next();
+    }
+
+    fn load_next(&mut self) {
+        let Some(job) = self.job.as_mut() else {
+            return;
+        };
+        let len = job.left.min(MAX_LEN);
+        if len == 0 {
+            return;
+        }
+        let (src, dst) = (job.src, job.dst);
+        let src_increase = job.src_increase;
+        let bytes = len * job.size;
+        if src_increase {
+            job.src += bytes;
+        }
+        job.dst += bytes;
+        job.left -= len;
+
+        self.ch.stop();
+        match job.size {
+            1 => self
+                .ch
+                .set_memory_to_memory::<u8>(src, src_increase, dst, true, len),
+            2 => self
+                .ch
+                .set_memory_to_memory::<u16>(src, src_increase, dst, true, len),
+            _ => self
+                .ch
+                .set_memory_to_memory::<u32>(src, src_increase, dst, true, len),
+        }
+        self.ch.start();
+    }
+}
@@ -1,5 +1,7 @@
 mod circular_buffer_rx;
 pub use circular_buffer_rx::*;
+mod memcpy;
+pub use memcpy::*;
 mod ringbuf_tx;
 pub use ringbuf_tx::*;
 
@@ -21,14 +23,18 @@ pub trait DmaChannel {
         self.set_transfer_length(buf.len());
     }
 
+    /// The transfer starts without any request from a peripheral once the channel is started.
     fn set_memory_to_memory<T: Sized + Copy>(
         &mut self,
         src_addr: usize,
+        src_increase: bool,
         dst_addr: usize,
+        dst_increase: bool,
         len: usize,
     );
 
     fn get_left_len(&self) -> usize;
+    /// It's `false` after a transfer error, which disables the channel.
     fn in_progress(&self) -> bool;
 
     fn set_interrupt(&mut self, event: DmaEvent, enable: bool);
@@ -39,4 +45,11 @@ pub trait DmaChannel {
 pub enum DmaEvent {
     TransferComplete,
     HalfTransfer,
+    TransferError,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaError {
+    /// A bus error on a read or a write, the channel is disabled.
+    TransferError,
 }
@@ -0,0 +1,285 @@
+use super::{page_ring::*, *};
+use core::{iter::StepBy, ops::Range};
+
+/// ID, value and CRC
+const RECORD_SIZE: u32 = 6;
+const ERASED: u16 = 0xFFFF;
+
+/// EEPROM emulation with 16-bit variables.
+///
+/// The variables are appended to the active page as records of ID, value and CRC. When the page
+/// is full, the latest values are transferred to the next page, so the pages are erased in turn.
+///
+/// A page is only erased after the next page is completely written, and a record with a wrong
+/// CRC is ignored, so a power loss at any time keeps either the old or the new value.
+///
+/// ```ignore
+/// let mut eeprom = Eeprom::new(flash.writer(SectorSize::Sz1K), 0x1_F000, 4).unwrap();
+/// eeprom.write(1, 1234).unwrap();
+/// assert_eq!(eeprom.read(1).unwrap(), Some(1234));
+/// ```
+pub struct Eeprom<F> {
+    pages: PageRing<F>,
+    /// Offset of the next free record in the active page
+    next_free: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EepromError<E> {
+    Flash(E),
+    /// The latest values of all the variables don't fit in a page.
+    Full,
+    /// The ID `0xFFFF` is reserved.
+    InvalidId,
+}
+
+impl<F> Eeprom<F>
+where
+    F: FlashStorage,
+{
+    /// Use `page_count` pages from `start_offset`, which must be aligned to a page.
+    /// It recovers from an interrupted operation, or formats the pages if there's no valid one.
+    pub fn new(
+        flash: F,
+        start_offset: u32,
+        page_count: usize,
+    ) -> Result<Self, EepromError<F::Error>> {
+        let mut eeprom = Self {
+            pages: PageRing::new(flash, start_offset, page_count),
+            next_free: HEADER_SIZE,
+        };
+        eeprom.pages.recover().map_err(EepromError::Flash)?;
+        eeprom.next_free = eeprom.pages.page_size;
+        for offset in eeprom.records(eeprom.pages.page_size) {
+            if eeprom.is_free(offset)? {
+                eeprom.next_free = offset;
+                break;
+            }
+        }
+        Ok(eeprom)
+    }
+
+    pub fn release(self) -> F {
+        self.pages.flash
+    }
+
+    /// Returns the latest value of the variable, or `None` if it has never been written.
+    pub fn read(&self, id: u16) -> Result<Option<u16>, EepromError<F::Error>> {
+        let mut value = None;
+        for offset in self.records(self.next_free) {
+            if let Some((rid, v)) = self.read_record(self.pages.active, offset)?
+                && rid == id
+            {
+                value = Some(v);
+            }
+        }
+        Ok(value)
+    }
+
+    pub fn write(&mut self, id: u16, value: u16) -> Result<(), EepromError<F::Error>> {
+        if id == ERASED {
+            return Err(EepromError::InvalidId);
+        }
+        if self.read(id)? == Some(value) {
+            return Ok(());
+        }
+        if self.next_free + RECORD_SIZE > self.pages.page_size {
+            return self.transfer(id, value);
+        }
+        let offset = self.next_free;
+        self.next_free += RECORD_SIZE;
+        self.write_record(self.pages.active, offset, id, value)
+    }
+
+    /// Erase all the variables.
+    pub fn format(&mut self) -> Result<(), EepromError<F::Error>> {
+        self.pages.format().map_err(EepromError::Flash)?;
+        self.next_free = HEADER_SIZE;
+        Ok(())
+    }
+
+    /// Copy the latest values and the new one to the next page.
+    fn transfer(&mut self, new_id: u16, new_value: u16) -> Result<(), EepromError<F::Error>> {
+        let from = self.pages.active;
+        let to = self.pages.begin_transfer().map_err(EepromError::Flash)?;
+
+        let mut dst = HEADER_SIZE;
+        let mut push = |eeprom: &mut Self, id, value| {
+            if dst + RECORD_SIZE > eeprom.pages.page_size {
+                return Err(EepromError::Full);
+            }
+            eeprom.write_record(to, dst, id, value)?;
+            dst += RECORD_SIZE;
+            Ok(())
+        };
+
+        for offset in self.records(self.next_free) {
+            let Some((id, value)) = self.read_record(from, offset)? else {
+                continue;
+            };
+            if id == new_id || self.has_later(from, offset, id)? {
+                continue;
+            }
+            push(self, id, value)?;
+        }
+        push(self, new_id, new_value)?;
+
+        self.pages.end_transfer().map_err(EepromError::Flash)?;
+        self.next_free = dst;
+        Ok(())
+    }
+
+    /// Whether a later valid record in the page has the same ID.
+    fn has_later(&self, page: usize, offset: u32, id: u16) -> Result<bool, EepromError<F::Error>> {
+        for later in self.records(self.next_free).filter(|&o| o > offset) {
+            if matches!(self.read_record(page, later)?, Some((rid, _)) if rid == id) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_free(&self, offset: u32) -> Result<bool, EepromError<F::Error>> {
+        self.pages
+            .is_erased(self.pages.active, offset, RECORD_SIZE as usize)
+            .map_err(EepromError::Flash)
+    }
+
+    /// Returns `None` if the record is not valid.
+    fn read_record(
+        &self,
+        page: usize,
+        offset: u32,
+    ) -> Result<Option<(u16, u16)>, EepromError<F::Error>> {
+        let mut buf = [0; RECORD_SIZE as usize];
+        self.pages
+            .read(page, offset, &mut buf)
+            .map_err(EepromError::Flash)?;
+        let id = u16::from_le_bytes([buf[0], buf[1]]);
+        let value = u16::from_le_bytes([buf[2], buf[3]]);
+        let crc = u16::from_le_bytes([buf[4], buf[5]]);
+        if id != ERASED && crc == crc16_update(0xFFFF, &buf[..4]) {
+            Ok(Some((id, value)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn write_record(
+        &mut self,
+        page: usize,
+        offset: u32,
+        id: u16,
+        value: u16,
+    ) -> Result<(), EepromError<F::Error>> {
+        let mut buf = [0; RECORD_SIZE as usize];
+        buf[..2].copy_from_slice(&id.to_le_bytes());
+        buf[2..4].copy_from_slice(&value.to_le_bytes());
+        let crc = crc16_update(0xFFFF, &buf[..4]);
+        buf[4..].copy_from_slice(&crc.to_le_bytes());
+        self.pages
+            .write(page, offset, &buf)
+            .map_err(EepromError::Flash)
+    }
+
+    /// Record offsets in a page before `end`
+    fn records(&self, end: u32) -> StepBy<Range<u32>> {
+        let last = self.pages.page_size - RECORD_SIZE;
+        (HEADER_SIZE..end.min(last + 1)).step_by(RECORD_SIZE as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_SIZE: usize = 64;
+    const PAGES: usize = 3;
+
+    fn new_eeprom() -> Eeprom<RamFlash> {
+        Eeprom::new(RamFlash::new(PAGE_SIZE, PAGES), 0, PAGES).unwrap()
+    }
+
+    #[test]
+    fn read_write() {
+        let mut eeprom = new_eeprom();
+        assert_eq!(eeprom.read(1), Ok(None));
+        eeprom.write(1, 100).unwrap();
+        eeprom.write(2, 200).unwrap();
+        eeprom.write(1, 101).unwrap();
+        assert_eq!(eeprom.read(1), Ok(Some(101)));
+        assert_eq!(eeprom.read(2), Ok(Some(200)));
+        assert_eq!(eeprom.write(ERASED, 0), Err(EepromError::InvalidId));
+
+        // Keep the values after a reset
+        let flash = eeprom.release();
+        let eeprom = Eeprom::new(flash, 0, PAGES).unwrap();
+        assert_eq!(eeprom.read(1), Ok(Some(101)));
+        assert_eq!(eeprom.read(2), Ok(Some(200)));
+    }
+
+    #[test]
+    fn transfer() {
+        let mut eeprom = new_eeprom();
+        // Many times more than a page can hold
+        for i in 0..200 {
+            eeprom.write(1, i).unwrap();
+            eeprom.write(2, i + 1000).unwrap();
+            eeprom.write(3 + i % 3, i).unwrap();
+        }
+        assert_eq!(eeprom.read(1), Ok(Some(199)));
+        assert_eq!(eeprom.read(2), Ok(Some(1199)));
+        assert_eq!(eeprom.read(3), Ok(Some(198)));
+        assert_eq!(eeprom.read(4), Ok(Some(199)));
+        assert_eq!(eeprom.read(5), Ok(Some(197)));
+
+        let flash = eeprom.release();
+        let eeprom = Eeprom::new(flash, 0, PAGES).unwrap();
+        assert_eq!(eeprom.read(1), Ok(Some(199)));
+        assert_eq!(eeprom.read(5), Ok(Some(197)));
+    }
+
+    #[test]
+    fn full() {
+        let mut eeprom = new_eeprom();
+        // (64 - 8) / 6 = 9 records per page
+        for id in 0..9 {
+            eeprom.write(id, id).unwrap();
+        }
+        assert_eq!(eeprom.write(9, 9), Err(EepromError::Full));
+    }
+
+    #[test]
+    fn power_loss() {
+        // Lose power after every possible number of programmed half-words
+        for limit in 0..100 {
+            let mut eeprom = new_eeprom();
+            for i in 0..7 {
+                eeprom.write(i, i).unwrap();
+            }
+
+            let mut flash = eeprom.release();
+            flash.set_write_limit(Some(limit));
+            let mut eeprom = Eeprom::new(flash, 0, PAGES).unwrap();
+            let mut done = 0;
+            for i in 0..7 {
+                if eeprom.write(i, i + 100).is_err() {
+                    break;
+                }
+                done += 1;
+            }
+
+            let mut flash = eeprom.release();
+            flash.set_write_limit(None);
+            let eeprom = Eeprom::new(flash, 0, PAGES).unwrap();
+            for i in 0..7 {
+                let value = eeprom.read(i).unwrap().unwrap();
+                if i < done {
+                    assert_eq!(value, i + 100, "limit {limit}");
+                } else {
+                    assert!(value == i || value == i + 100, "limit {limit}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,601 @@
+use super::{page_ring::*, *};
+
+/// Maximum length of a serialized key
+pub const KV_MAX_KEY_LEN: usize = 32;
+/// Key length, kind, value length and CRC
+const RECORD_HEADER_SIZE: u32 = 6;
+const KIND_REMOVED: u8 = 0;
+const KIND_VALUE: u8 = 1;
+/// Size of the buffers on the stack
+const CHUNK_SIZE: usize = 32;
+
+/// Key of a [KvStore], serialized into at most [KV_MAX_KEY_LEN] bytes.
+pub trait KvKey {
+    /// Returns the serialized length, or `None` if the buffer is too small.
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize>;
+}
+
+/// Value of a [KvStore]
+pub trait KvValue<'a>: Sized {
+    /// Returns the serialized length, or `None` if the buffer is too small.
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize>;
+    fn deserialize_from(buf: &'a [u8]) -> Option<Self>;
+}
+
+/// A log-structured key/value store, with an interface in the style of `sequential-storage`.
+///
+/// Items are appended to the active page, each one with a CRC. When the page is full, the latest
+/// items are transferred to the next page and the old page is erased. So all the live items must
+/// fit in a page, and more pages spread the wear.
+///
+/// A power loss at any time keeps either the old or the new value of an item.
+///
+/// ```ignore
+/// let mut kv = KvStore::new(flash.writer(SectorSize::Sz1K), 0x1_E000, 4).unwrap();
+/// let mut buf = [0; 128];
+/// kv.store_item("name", &b"device-01".as_slice(), &mut buf).unwrap();
+/// kv.store_item(&7u16, &1000u32, &mut buf).unwrap();
+/// let name: Option<&[u8]> = kv.fetch_item("name", &mut buf).unwrap();
+/// ```
+pub struct KvStore<F> {
+    pages: PageRing<F>,
+    /// Offset of the free space in the active page
+    next_free: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvError<E> {
+    Flash(E),
+    /// The latest items don't fit in a page.
+    Full,
+    /// The buffer is too small for the item, or the key is longer than [KV_MAX_KEY_LEN].
+    BufferTooSmall,
+    /// The stored value can't be deserialized into the requested type.
+    Deserialize,
+}
+
+impl<F> KvStore<F>
+where
+    F: FlashStorage,
+{
+    /// Use `page_count` pages from `start_offset`, which must be aligned to a page.
+    /// It recovers from an interrupted operation, or formats the pages if there's no valid one.
+    pub fn new(flash: F, start_offset: u32, page_count: usize) -> Result<Self, KvError<F::Error>> {
+        let mut kv = Self {
+            pages: PageRing::new(flash, start_offset, page_count),
+            next_free: HEADER_SIZE,
+        };
+        kv.pages.recover().map_err(KvError::Flash)?;
+
+        let mut offset = HEADER_SIZE;
+        loop {
+            match kv.scan(kv.pages.active, offset)? {
+                Scan::Record(r, _) => offset += r.size(),
+                Scan::Free => break,
+                // Don't write after a broken record, it will be collected by the next write.
+                Scan::Broken => {
+                    offset = kv.pages.page_size;
+                    break;
+                }
+            }
+        }
+        kv.next_free = offset;
+        Ok(kv)
+    }
+
+    pub fn release(self) -> F {
+        self.pages.flash
+    }
+
+    /// Returns the latest value of the item, deserialized from `buf`.
+    pub fn fetch_item<'d, K, V>(
+        &self,
+        key: &K,
+        buf: &'d mut [u8],
+    ) -> Result<Option<V>, KvError<F::Error>>
+    where
+        K: KvKey + ?Sized,
+        V: KvValue<'d>,
+    {
+        let mut key_buf = [0; KV_MAX_KEY_LEN];
+        let key = serialize_key(key, &mut key_buf)?;
+
+        let mut found = None;
+        for r in self.records(self.pages.active) {
+            let (r, valid) = r?;
+            if valid && self.key_matches(self.pages.active, &r, key)? {
+                found = Some(r);
+            }
+        }
+
+        match found {
+            Some(r) if r.kind == KIND_VALUE => {
+                let value = buf.get_mut(..r.value_len).ok_or(KvError::BufferTooSmall)?;
+                self.pages
+                    .read_unaligned(self.pages.active, r.value_offset(), value)
+                    .map_err(KvError::Flash)?;
+                V::deserialize_from(value)
+                    .map(Some)
+                    .ok_or(KvError::Deserialize)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Store an item, `buf` is used to serialize the whole record.
+    pub fn store_item<'d, K, V>(
+        &mut self,
+        key: &K,
+        value: &V,
+        buf: &mut [u8],
+    ) -> Result<(), KvError<F::Error>>
+    where
+        K: KvKey + ?Sized,
+        V: KvValue<'d>,
+    {
+        let header = RECORD_HEADER_SIZE as usize;
+        let body = buf.get_mut(header..).ok_or(KvError::BufferTooSmall)?;
+        let key_len = serialize_key(key, body)?.len();
+        let value_len = value
+            .serialize_into(&mut body[key_len..])
+            .ok_or(KvError::BufferTooSmall)?;
+        let size = build_record(buf, KIND_VALUE, key_len, value_len)?;
+        self.append(&buf[..size], key_len)
+    }
+
+    /// Remove an item if it exists.
+    pub fn remove_item<K>(&mut self, key: &K) -> Result<(), KvError<F::Error>>
+    where
+        K: KvKey + ?Sized,
+    {
+        let mut buf = [0; RECORD_HEADER_SIZE as usize + KV_MAX_KEY_LEN + 1];
+        let key_len = serialize_key(key, &mut buf[RECORD_HEADER_SIZE as usize..])?.len();
+        let size = build_record(&mut buf, KIND_REMOVED, key_len, 0)?;
+        self.append(&buf[..size], key_len)
+    }
+
+    /// Remove all the items.
+    pub fn format(&mut self) -> Result<(), KvError<F::Error>> {
+        self.pages.format().map_err(KvError::Flash)?;
+        self.next_free = HEADER_SIZE;
+        Ok(())
+    }
+
+    fn append(&mut self, record: &[u8], key_len: usize) -> Result<(), KvError<F::Error>> {
+        if self.next_free + record.len() as u32 > self.pages.page_size {
+            return self.collect_garbage(record, key_len);
+        }
+        self.pages
+            .write(self.pages.active, self.next_free, record)
+            .map_err(KvError::Flash)?;
+        self.next_free += record.len() as u32;
+        Ok(())
+    }
+
+    /// Copy the latest items and the new record to the next page.
+    fn collect_garbage(&mut self, new: &[u8], key_len: usize) -> Result<(), KvError<F::Error>> {
+        let from = self.pages.active;
+        let new_key = &new[RECORD_HEADER_SIZE as usize..][..key_len];
+        let to = self.pages.begin_transfer().map_err(KvError::Flash)?;
+
+        let mut dst = HEADER_SIZE;
+        let mut offset = HEADER_SIZE;
+        while offset < self.next_free {
+            let Scan::Record(r, valid) = self.scan(from, offset)? else {
+                break;
+            };
+            offset += r.size();
+            if !valid
+                || r.kind != KIND_VALUE
+                || self.key_matches(from, &r, new_key)?
+                || self.has_later(from, &r)?
+            {
+                continue;
+            }
+            if dst + r.size() > self.pages.page_size {
+                return Err(KvError::Full);
+            }
+            self.copy_record(from, &r, to, dst)?;
+            dst += r.size();
+        }
+
+        // Removed items are not copied, so there's no need to keep the record.
+        if new[1] == KIND_VALUE {
+            if dst + new.len() as u32 > self.pages.page_size {
+                return Err(KvError::Full);
+            }
+            self.pages.write(to, dst, new).map_err(KvError::Flash)?;
+            dst += new.len() as u32;
+        }
+
+        self.pages.end_transfer().map_err(KvError::Flash)?;
+        self.next_free = dst;
+        Ok(())
+    }
+
+    /// Whether a later valid record in the page has the same key.
+    fn has_later(&self, page: usize, record: &Record) -> Result<bool, KvError<F::Error>> {
+        let mut key = [0; KV_MAX_KEY_LEN];
+        let key = &mut key[..record.key_len];
+        self.pages
+            .read(page, record.key_offset(), key)
+            .map_err(KvError::Flash)?;
+
+        for r in self.records(page) {
+            let (r, valid) = r?;
+            if r.offset > record.offset && valid && self.key_matches(page, &r, key)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn key_matches(&self, page: usize, r: &Record, key: &[u8]) -> Result<bool, KvError<F::Error>> {
+        if r.key_len != key.len() {
+            return Ok(false);
+        }
+        let mut buf = [0; KV_MAX_KEY_LEN];
+        let buf = &mut buf[..r.key_len];
+        self.pages
+            .read(page, r.key_offset(), buf)
+            .map_err(KvError::Flash)?;
+        Ok(buf == key)
+    }
+
+    fn copy_record(
+        &mut self,
+        from: usize,
+        r: &Record,
+        to: usize,
+        dst: u32,
+    ) -> Result<(), KvError<F::Error>> {
+        let mut buf = [0; CHUNK_SIZE];
+        let mut done = 0;
+        while done < r.size() {
+            let n = ((r.size() - done) as usize).min(CHUNK_SIZE);
+            self.pages
+                .read(from, r.offset + done, &mut buf[..n])
+                .map_err(KvError::Flash)?;
+            self.pages
+                .write(to, dst + done, &buf[..n])
+                .map_err(KvError::Flash)?;
+            done += n as u32;
+        }
+        Ok(())
+    }
+
+    /// Iterate over the records in a page, with whether the CRC matches.
+    fn records(
+        &self,
+        page: usize,
+    ) -> impl Iterator<Item = Result<(Record, bool), KvError<F::Error>>> {
+        let end = if page == self.pages.active {
+            self.next_free
+        } else {
+            self.pages.page_size
+        };
+        let mut offset = HEADER_SIZE;
+        core::iter::from_fn(move || {
+            if offset >= end {
+                return None;
+            }
+            match self.scan(page, offset) {
+                Ok(Scan::Record(r, valid)) => {
+                    offset += r.size();
+                    Some(Ok((r, valid)))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    offset = end;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    fn scan(&self, page: usize, offset: u32) -> Result<Scan, KvError<F::Error>> {
+        if offset + RECORD_HEADER_SIZE > self.pages.page_size {
+            return Ok(Scan::Free);
+        }
+        let mut header = [0; RECORD_HEADER_SIZE as usize];
+        self.pages
+            .read(page, offset, &mut header)
+            .map_err(KvError::Flash)?;
+        if header.iter().all(|&b| b == 0xFF) {
+            return Ok(Scan::Free);
+        }
+
+        let r = Record {
+            offset,
+            key_len: header[0] as usize,
+            kind: header[1],
+            value_len: u16::from_le_bytes([header[2], header[3]]) as usize,
+        };
+        if r.key_len == 0
+            || r.key_len > KV_MAX_KEY_LEN
+            || !matches!(r.kind, KIND_VALUE | KIND_REMOVED)
+            || offset + r.size() > self.pages.page_size
+        {
+            return Ok(Scan::Broken);
+        }
+
+        let mut crc = crc16_update(0xFFFF, &header[..4]);
+        let mut buf = [0; CHUNK_SIZE];
+        let mut pos = r.key_offset();
+        let end = pos + (r.key_len + r.value_len) as u32;
+        while pos < end {
+            let n = ((end - pos) as usize).min(CHUNK_SIZE);
+            // Keep the reads aligned to half-words
+            let aligned = (n + 1) & !1;
+            self.pages
+                .read(page, pos, &mut buf[..aligned])
+                .map_err(KvError::Flash)?;
+            crc = crc16_update(crc, &buf[..n]);
+            pos += n as u32;
+        }
+        let valid = crc == u16::from_le_bytes([header[4], header[5]]);
+        Ok(Scan::Record(r, valid))
+    }
+}
+
+struct Record {
+    offset: u32,
+    key_len: usize,
+    kind: u8,
+    value_len: usize,
+}
+
+impl Record {
+    #[inline]
+    fn size(&self) -> u32 {
+        RECORD_HEADER_SIZE + align2(self.key_len + self.value_len) as u32
+    }
+
+    #[inline]
+    fn key_offset(&self) -> u32 {
+        self.offset + RECORD_HEADER_SIZE
+    }
+
+    #[inline]
+    fn value_offset(&self) -> u32 {
+        self.key_offset() + self.key_len as u32
+    }
+}
+
+enum Scan {
+    Record(Record, bool),
+    Free,
+    /// Interrupted while writing a record header
+    Broken,
+}
+
+// Key and value types --------------------------------------------------------
+
+macro_rules! impl_int {
+    ($($T:ty),+) => {$(
+        impl KvKey for $T {
+            fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+                KvValue::serialize_into(self, buf)
+            }
+        }
+
+        impl KvValue<'_> for $T {
+            fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+                let bytes = self.to_le_bytes();
+                buf.get_mut(..bytes.len())?.copy_from_slice(&bytes);
+                Some(bytes.len())
+            }
+
+            fn deserialize_from(buf: &[u8]) -> Option<Self> {
+                Some(Self::from_le_bytes(buf.try_into().ok()?))
+            }
+        }
+    )+};
+}
+
+impl_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl KvKey for [u8] {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        buf.get_mut(..self.len())?.copy_from_slice(self);
+        Some(self.len())
+    }
+}
+
+impl KvKey for str {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        KvKey::serialize_into(self.as_bytes(), buf)
+    }
+}
+
+impl<'a> KvValue<'a> for &'a [u8] {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        KvKey::serialize_into(*self, buf)
+    }
+
+    fn deserialize_from(buf: &'a [u8]) -> Option<Self> {
+        Some(buf)
+    }
+}
+
+impl<'a> KvValue<'a> for &'a str {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        KvKey::serialize_into(self.as_bytes(), buf)
+    }
+
+    fn deserialize_from(buf: &'a [u8]) -> Option<Self> {
+        core::str::from_utf8(buf).ok()
+    }
+}
+
+impl<const N: usize> KvValue<'_> for [u8; N] {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        KvKey::serialize_into(self.as_slice(), buf)
+    }
+
+    fn deserialize_from(buf: &[u8]) -> Option<Self> {
+        buf.try_into().ok()
+    }
+}
+
+impl KvValue<'_> for bool {
+    fn serialize_into(&self, buf: &mut [u8]) -> Option<usize> {
+        KvValue::serialize_into(&(*self as u8), buf)
+    }
+
+    fn deserialize_from(buf: &[u8]) -> Option<Self> {
+        u8::deserialize_from(buf).map(|v| v != 0)
+    }
+}
+
+// Utilities ------------------------------------------------------------------
+
+#[inline]
+const fn align2(len: usize) -> usize {
+    (len + 1) & !1
+}
+
+fn serialize_key<'b, K, E>(key: &K, buf: &'b mut [u8]) -> Result<&'b [u8], KvError<E>>
+where
+    K: KvKey + ?Sized,
+{
+    let max = buf.len().min(KV_MAX_KEY_LEN);
+    let len = key
+        .serialize_into(&mut buf[..max])
+        .filter(|&len| len > 0)
+        .ok_or(KvError::BufferTooSmall)?;
+    Ok(&buf[..len])
+}
+
+/// Fill in the header and the padding of a record, whose key and value are already in `buf`.
+/// Returns the record size.
+fn build_record<E>(
+    buf: &mut [u8],
+    kind: u8,
+    key_len: usize,
+    value_len: usize,
+) -> Result<usize, KvError<E>> {
+    let header = RECORD_HEADER_SIZE as usize;
+    let body_len = key_len + value_len;
+    let size = header + align2(body_len);
+    if size > buf.len() || value_len > u16::MAX as usize {
+        return Err(KvError::BufferTooSmall);
+    }
+    buf[0] = key_len as u8;
+    buf[1] = kind;
+    buf[2..4].copy_from_slice(&(value_len as u16).to_le_bytes());
+    let crc = crc16_update(0xFFFF, &buf[..4]);
+    let crc = crc16_update(crc, &buf[header..header + body_len]);
+    buf[4..6].copy_from_slice(&crc.to_le_bytes());
+    buf[header + body_len..size].fill(0xFF);
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_SIZE: usize = 256;
+    const PAGES: usize = 3;
+
+    fn new_store() -> KvStore<RamFlash> {
+        KvStore::new(RamFlash::new(PAGE_SIZE, PAGES), 0, PAGES).unwrap()
+    }
+
+    #[test]
+    fn store_fetch() {
+        let mut kv = new_store();
+        let mut buf = [0; 64];
+        kv.store_item("name", &"device-01", &mut buf).unwrap();
+        kv.store_item(&7u16, &1000u32, &mut buf).unwrap();
+        kv.store_item("on", &true, &mut buf).unwrap();
+        kv.store_item(&7u16, &1001u32, &mut buf).unwrap();
+
+        assert_eq!(kv.fetch_item("name", &mut buf), Ok(Some("device-01")));
+        assert_eq!(kv.fetch_item(&7u16, &mut buf), Ok(Some(1001u32)));
+        assert_eq!(kv.fetch_item("on", &mut buf), Ok(Some(true)));
+        assert_eq!(kv.fetch_item::<_, u32>("none", &mut buf), Ok(None));
+        assert_eq!(
+            kv.fetch_item::<_, u16>(&7u16, &mut buf),
+            Err(KvError::Deserialize)
+        );
+        assert_eq!(
+            kv.fetch_item::<_, &str>("name", &mut buf[..4]),
+            Err(KvError::BufferTooSmall)
+        );
+
+        kv.remove_item("on").unwrap();
+        assert_eq!(kv.fetch_item::<_, bool>("on", &mut buf), Ok(None));
+
+        // Keep the items after a reset
+        let flash = kv.release();
+        let kv = KvStore::new(flash, 0, PAGES).unwrap();
+        assert_eq!(kv.fetch_item("name", &mut buf), Ok(Some("device-01")));
+        assert_eq!(kv.fetch_item(&7u16, &mut buf), Ok(Some(1001u32)));
+        assert_eq!(kv.fetch_item::<_, bool>("on", &mut buf), Ok(None));
+    }
+
+    #[test]
+    fn garbage_collection() {
+        let mut kv = new_store();
+        let mut buf = [0; 128];
+        let blob = [0x5A_u8; 100];
+        for i in 0..100_u32 {
+            kv.store_item("blob", &blob, &mut buf).unwrap();
+            kv.store_item("count", &i, &mut buf).unwrap();
+            kv.store_item(&(i % 3) as &u32, &i, &mut buf).unwrap();
+        }
+        assert_eq!(kv.fetch_item("blob", &mut buf), Ok(Some(blob)));
+        assert_eq!(kv.fetch_item("count", &mut buf), Ok(Some(99_u32)));
+        assert_eq!(kv.fetch_item(&0_u32, &mut buf), Ok(Some(99_u32)));
+        assert_eq!(kv.fetch_item(&1_u32, &mut buf), Ok(Some(97_u32)));
+
+        // Too much live data
+        let big = [0_u8; 120];
+        assert_eq!(
+            kv.store_item("big", &big, &mut [0; 160]),
+            Err(KvError::Full)
+        );
+    }
+
+    #[test]
+    fn power_loss() {
+        let mut buf = [0; 128];
+        let blob = [0xA5_u8; 60];
+        // Lose power after every possible number of programmed half-words
+        for limit in 0..200 {
+            let mut kv = new_store();
+            kv.store_item("blob", &blob, &mut buf).unwrap();
+            for i in 0..4_u32 {
+                kv.store_item(&i, &i, &mut buf).unwrap();
+            }
+
+            let mut flash = kv.release();
+            flash.set_write_limit(Some(limit));
+            let mut kv = KvStore::new(flash, 0, PAGES).unwrap();
+            let mut done = 0;
+            for i in 0..4_u32 {
+                if kv.store_item(&i, &(i + 100), &mut buf).is_err() {
+                    break;
+                }
+                kv.store_item("blob", &blob, &mut buf).ok();
+                done += 1;
+            }
+
+            let mut flash = kv.release();
+            flash.set_write_limit(None);
+            let mut kv = KvStore::new(flash, 0, PAGES).unwrap();
+            assert_eq!(kv.fetch_item("blob", &mut buf), Ok(Some(blob)));
+            for i in 0..4_u32 {
+                let value: u32 = kv.fetch_item(&i, &mut buf).unwrap().unwrap();
+                if i < done {
+                    assert_eq!(value, i + 100, "limit {limit}");
+                } else {
+                    assert!(value == i || value == i + 100, "limit {limit}");
+                }
+            }
+            // Still writable
+            kv.store_item(&0_u32, &0_u32, &mut buf).unwrap();
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::crc16::crc16_update;
+
+mod eeprom;
+pub use eeprom::*;
+mod kv_store;
+pub use kv_store::*;
+mod page_ring;
+#[cfg(feature = "std")]
+mod ram_flash;
+#[cfg(feature = "std")]
+pub use ram_flash::*;
+
+/// Flash memory that is erased by pages and programmed by half-words.
+pub trait FlashStorage {
+    type Error: core::fmt::Debug;
+
+    /// Size of an erasable page in bytes
+    fn page_size(&self) -> usize;
+
+    /// `offset` must be a multiple of 2.
+    fn read(&self, offset: u32, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Erase the page which contains `offset`.
+    fn erase_page(&mut self, offset: u32) -> Result<(), Self::Error>;
+
+    /// `offset` and the length must be multiples of 2. Only erased half-words can be programmed,
+    /// except that any half-word can be programmed to `0`.
+    fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), Self::Error>;
+}
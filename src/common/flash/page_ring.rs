@@ -0,0 +1,190 @@
+use super::*;
+
+/// Markers in the page header, set by programming them to 0.
+const MARK_RECEIVE: u32 = 0;
+const SEQUENCE: u32 = 2;
+const MARK_VALID: u32 = 4;
+const MARK_OBSOLETE: u32 = 6;
+pub(super) const HEADER_SIZE: u32 = 8;
+const MARK_SET: u16 = 0;
+const ERASED: u16 = 0xFFFF;
+
+/// Pages used in turn, only one of them is active.
+///
+/// The live data is transferred to the next page when the active page is full. The active page is
+/// only erased after the next page is completely written, so a power loss at any time leaves
+/// either the old or the new page valid.
+pub(super) struct PageRing<F> {
+    pub(super) flash: F,
+    start: u32,
+    pub(super) page_size: u32,
+    page_count: usize,
+    pub(super) active: usize,
+    sequence: u16,
+}
+
+impl<F> PageRing<F>
+where
+    F: FlashStorage,
+{
+    pub(super) fn new(flash: F, start_offset: u32, page_count: usize) -> Self {
+        assert!(page_count >= 2, "at least 2 pages are needed");
+        let page_size = flash.page_size() as u32;
+        assert!(start_offset.is_multiple_of(page_size));
+        Self {
+            flash,
+            start: start_offset,
+            page_size,
+            page_count,
+            active: 0,
+            sequence: 0,
+        }
+    }
+
+    /// Find the newest valid page and erase the pages left by an interrupted transfer.
+    /// All the pages are formatted if there's no valid one.
+    pub(super) fn recover(&mut self) -> Result<(), F::Error> {
+        let mut newest: Option<(usize, u16)> = None;
+        for page in 0..self.page_count {
+            if self.is_valid(page)? {
+                let seq = self.read_u16(page, SEQUENCE)?;
+                if newest.is_none_or(|(_, s)| is_newer(seq, s)) {
+                    newest = Some((page, seq));
+                }
+            }
+        }
+
+        let Some((active, sequence)) = newest else {
+            return self.format();
+        };
+        self.active = active;
+        self.sequence = sequence;
+
+        for page in (0..self.page_count).filter(|&p| p != active) {
+            if !self.is_blank_header(page)? {
+                self.erase(page)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Erase all the pages and use the first one.
+    pub(super) fn format(&mut self) -> Result<(), F::Error> {
+        for page in 0..self.page_count {
+            self.erase(page)?;
+        }
+        self.active = 0;
+        self.sequence = 0;
+        self.start_page(0, 0)?;
+        self.write_u16(0, MARK_VALID, MARK_SET)
+    }
+
+    /// Erase and prepare the next page, returns it.
+    pub(super) fn begin_transfer(&mut self) -> Result<usize, F::Error> {
+        let to = self.next_page();
+        self.erase(to)?;
+        self.start_page(to, self.sequence.wrapping_add(1))?;
+        Ok(to)
+    }
+
+    /// Make the next page active, then erase the old one.
+    pub(super) fn end_transfer(&mut self) -> Result<(), F::Error> {
+        let from = self.active;
+        let to = self.next_page();
+        self.write_u16(to, MARK_VALID, MARK_SET)?;
+        self.active = to;
+        self.sequence = self.sequence.wrapping_add(1);
+
+        self.write_u16(from, MARK_OBSOLETE, MARK_SET)?;
+        self.erase(from)
+    }
+
+    pub(super) fn read(&self, page: usize, offset: u32, buf: &mut [u8]) -> Result<(), F::Error> {
+        self.flash.read(self.page_offset(page) + offset, buf)
+    }
+
+    /// Read from any offset, the flash is read by half-words.
+    pub(super) fn read_unaligned(
+        &self,
+        page: usize,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<(), F::Error> {
+        let mut chunk = [0; 34];
+        let mut done = 0;
+        while done < buf.len() {
+            let pos = offset + done as u32;
+            let skip = (pos & 1) as usize;
+            let n = (buf.len() - done).min(32);
+            self.read(page, pos - skip as u32, &mut chunk[..skip + n])?;
+            buf[done..done + n].copy_from_slice(&chunk[skip..skip + n]);
+            done += n;
+        }
+        Ok(())
+    }
+
+    pub(super) fn write(&mut self, page: usize, offset: u32, data: &[u8]) -> Result<(), F::Error> {
+        self.flash.write(self.page_offset(page) + offset, data)
+    }
+
+    /// Whether the bytes are all erased.
+    pub(super) fn is_erased(&self, page: usize, offset: u32, len: usize) -> Result<bool, F::Error> {
+        let mut buf = [0; 8];
+        let mut offset = offset;
+        let end = offset + len as u32;
+        while offset < end {
+            let n = ((end - offset) as usize).min(buf.len());
+            self.read(page, offset, &mut buf[..n])?;
+            if buf[..n].iter().any(|&b| b != 0xFF) {
+                return Ok(false);
+            }
+            offset += n as u32;
+        }
+        Ok(true)
+    }
+
+    fn start_page(&mut self, page: usize, sequence: u16) -> Result<(), F::Error> {
+        self.write_u16(page, SEQUENCE, sequence)?;
+        self.write_u16(page, MARK_RECEIVE, MARK_SET)
+    }
+
+    fn is_valid(&self, page: usize) -> Result<bool, F::Error> {
+        Ok(self.read_u16(page, MARK_VALID)? == MARK_SET
+            && self.read_u16(page, MARK_OBSOLETE)? == ERASED)
+    }
+
+    fn is_blank_header(&self, page: usize) -> Result<bool, F::Error> {
+        self.is_erased(page, 0, HEADER_SIZE as usize)
+    }
+
+    fn read_u16(&self, page: usize, offset: u32) -> Result<u16, F::Error> {
+        let mut buf = [0; 2];
+        self.read(page, offset, &mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn write_u16(&mut self, page: usize, offset: u32, value: u16) -> Result<(), F::Error> {
+        self.write(page, offset, &value.to_le_bytes())
+    }
+
+    fn erase(&mut self, page: usize) -> Result<(), F::Error> {
+        self.flash.erase_page(self.page_offset(page))
+    }
+
+    #[inline]
+    fn next_page(&self) -> usize {
+        (self.active + 1) % self.page_count
+    }
+
+    #[inline]
+    fn page_offset(&self, page: usize) -> u32 {
+        self.start + page as u32 * self.page_size
+    }
+}
+
+// Utilities ------------------------------------------------------------------
+
+/// Whether sequence number `a` is newer than `b`, allowing wraparound.
+fn is_newer(a: u16, b: u16) -> bool {
+    (a.wrapping_sub(b) as i16) > 0
+}
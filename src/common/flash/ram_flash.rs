@@ -0,0 +1,100 @@
+use super::*;
+
+/// A flash memory in RAM for testing, with the same programming rules as the hardware.
+pub struct RamFlash {
+    data: Vec<u8>,
+    page_size: usize,
+    write_limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RamFlashError {
+    OutOfBounds,
+    Misaligned,
+    /// Programming a half-word that is not erased
+    NotErased,
+    /// The write limit is reached
+    PowerLoss,
+}
+
+impl RamFlash {
+    pub fn new(page_size: usize, page_count: usize) -> Self {
+        Self {
+            data: vec![0xFF; page_size * page_count],
+            page_size,
+            write_limit: None,
+        }
+    }
+
+    /// Simulate a power loss. Only `limit` more half-words can be programmed or pages erased,
+    /// the later operations fail with [RamFlashError::PowerLoss].
+    pub fn set_write_limit(&mut self, limit: Option<usize>) {
+        self.write_limit = limit;
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn consume(&mut self) -> Result<(), RamFlashError> {
+        match self.write_limit.as_mut() {
+            Some(0) => Err(RamFlashError::PowerLoss),
+            Some(n) => {
+                *n -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl FlashStorage for RamFlash {
+    type Error = RamFlashError;
+
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn read(&self, offset: u32, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let start = offset as usize;
+        if start & 1 != 0 {
+            return Err(RamFlashError::Misaligned);
+        }
+        let src = self
+            .data
+            .get(start..start + buf.len())
+            .ok_or(RamFlashError::OutOfBounds)?;
+        buf.copy_from_slice(src);
+        Ok(())
+    }
+
+    fn erase_page(&mut self, offset: u32) -> Result<(), Self::Error> {
+        let start = offset as usize / self.page_size * self.page_size;
+        if start >= self.data.len() {
+            return Err(RamFlashError::OutOfBounds);
+        }
+        self.consume()?;
+        self.data[start..start + self.page_size].fill(0xFF);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), Self::Error> {
+        let start = offset as usize;
+        if start & 1 != 0 || data.len() & 1 != 0 {
+            return Err(RamFlashError::Misaligned);
+        }
+        if start + data.len() > self.data.len() {
+            return Err(RamFlashError::OutOfBounds);
+        }
+        for (i, hword) in data.chunks_exact(2).enumerate() {
+            let at = start + i * 2;
+            let erased = self.data[at..at + 2] == [0xFF, 0xFF];
+            if !erased && hword != [0, 0] {
+                return Err(RamFlashError::NotErased);
+            }
+            self.consume()?;
+            self.data[at..at + 2].copy_from_slice(hword);
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
+pub(crate) mod crc16;
 pub mod dma;
+pub mod flash;
 pub mod os;
 pub mod ringbuf;
 pub mod simplest_heap;
 pub mod timer;
 pub mod uart;
 pub mod wrap_trait;
+pub mod ymodem;
@@ -0,0 +1,127 @@
+use super::*;
+
+pub struct CaptureTimer<TIM> {
+    tim: TIM,
+    clk: Hertz,
+}
+impl<TIM: GeneralTimer> CaptureTimer<TIM> {
+    pub fn new(tim: TIM, clk: Hertz) -> Self {
+        Self { tim, clk }
+    }
+
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.tim.reset_counter();
+        self.tim.enable_counter();
+    }
+
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        self.tim.disable_counter();
+    }
+
+    #[inline]
+    pub fn get_count_value(&self) -> u32 {
+        self.tim.read_count()
+    }
+
+    /// The captured values are in ticks of this frequency.
+    #[inline]
+    pub fn get_tick_freq(&self) -> Hertz {
+        self.clk / (self.tim.read_prescaler() as u32 + 1)
+    }
+
+    /// The counter wraps around after this value.
+    #[inline]
+    pub fn get_max_count(&self) -> u32 {
+        self.tim.read_auto_reload()
+    }
+}
+
+// Channels -------------------------------------------------------------------
+
+macro_rules! capture_channel {
+    ($name:ident, $Tim:ident, $ch:expr, $en:ident, $get_cc:ident, $event:expr) => {
+        pub struct $name<TIM> {
+            tim: TIM,
+        }
+
+        impl<TIM> $name<TIM> {
+            pub fn new(tim: TIM) -> Self {
+                Self { tim }
+            }
+        }
+
+        impl<TIM: $Tim + TimerWithInputCapture> CaptureChannel for $name<TIM> {
+            #[inline]
+            fn config(
+                &mut self,
+                edge: CaptureEdge,
+                prescaler: CapturePrescaler,
+                filter: InputFilter,
+            ) {
+                self.tim.$en(false);
+                self.tim
+                    .config_input_capture($ch, CaptureInput::Direct, prescaler, filter);
+                self.tim.set_capture_edge($ch, edge);
+            }
+
+            #[inline(always)]
+            fn set_enable(&mut self, en: bool) {
+                self.tim.$en(en);
+            }
+
+            #[inline]
+            fn read_capture(&mut self) -> Option<u32> {
+                if self.tim.get_interrupt_flag().contains($event) {
+                    // Reading the value clears the flag.
+                    Some(self.tim.$get_cc())
+                } else {
+                    None
+                }
+            }
+
+            #[inline]
+            fn is_overcaptured(&mut self) -> bool {
+                if self.tim.is_overcaptured($ch) {
+                    self.tim.clear_overcapture($ch);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    };
+}
+capture_channel!(
+    CaptureChannel1,
+    TimerWithPwm1Ch,
+    Channel::C1,
+    enable_ch1,
+    get_ch1_cc_value,
+    Event::C1
+);
+capture_channel!(
+    CaptureChannel2,
+    TimerWithPwm2Ch,
+    Channel::C2,
+    enable_ch2,
+    get_ch2_cc_value,
+    Event::C2
+);
+capture_channel!(
+    CaptureChannel3,
+    TimerWithPwm3Ch,
+    Channel::C3,
+    enable_ch3,
+    get_ch3_cc_value,
+    Event::C3
+);
+capture_channel!(
+    CaptureChannel4,
+    TimerWithPwm4Ch,
+    Channel::C4,
+    enable_ch4,
+    get_ch4_cc_value,
+    Event::C4
+);
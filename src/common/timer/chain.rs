@@ -0,0 +1,54 @@
+use super::*;
+
+/// A 32-bit counter of two chained 16-bit timers.
+///
+/// The update event of the low timer is sent to its trigger output, which clocks the high timer
+/// in the external clock mode 1.
+pub struct ChainedCounter<LOW, HIGH> {
+    low: LOW,
+    high: HIGH,
+    clk: Hertz,
+}
+
+impl<LOW: GeneralTimer, HIGH: GeneralTimer> ChainedCounter<LOW, HIGH> {
+    pub fn new(low: LOW, high: HIGH, clk: Hertz) -> Self {
+        Self { low, high, clk }
+    }
+
+    /// The high timer is started first, so it doesn't miss an overflow of the low timer.
+    pub fn start(&mut self) {
+        self.high.enable_counter();
+        self.low.enable_counter();
+    }
+
+    pub fn stop(&mut self) {
+        self.low.disable_counter();
+        self.high.disable_counter();
+    }
+
+    pub fn reset(&mut self) {
+        self.low.reset_counter();
+        self.high.reset_counter();
+    }
+
+    /// The counter counts at this frequency.
+    #[inline]
+    pub fn get_tick_freq(&self) -> Hertz {
+        self.clk / (self.low.read_prescaler() as u32 + 1)
+    }
+
+    pub fn read_count(&self) -> u32 {
+        loop {
+            let high = self.high.read_count();
+            let low = self.low.read_count();
+            // The low timer didn't overflow between the two readings of the high timer.
+            if self.high.read_count() == high {
+                return (high << 16) | low;
+            }
+        }
+    }
+
+    pub fn release(self) -> (LOW, HIGH) {
+        (self.low, self.high)
+    }
+}
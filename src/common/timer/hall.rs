@@ -0,0 +1,99 @@
+use super::*;
+use embedded_hal::digital::InputPin;
+
+/// Hall sector of each state in the 120° sequence 1, 3, 2, 6, 4, 5. The states 0 and 7 are
+/// invalid.
+const SECTORS: [Option<u8>; 8] = [
+    None,
+    Some(0),
+    Some(2),
+    Some(1),
+    Some(4),
+    Some(5),
+    Some(3),
+    None,
+];
+
+/// Hall sensor interface of a BLDC motor.
+///
+/// The 3 Hall inputs are combined by XOR on TI1. Every edge of any input resets the counter and
+/// is captured by channel 1, so the capture value is the time between two edges. The trigger
+/// output sends a pulse at each edge, which can switch the commutation of an advanced timer
+/// with [PwmTimer::enable_commutation_on_trigger].
+pub struct HallSensor<TIM, H1, H2, H3> {
+    tim: TIM,
+    clk: Hertz,
+    pins: (H1, H2, H3),
+}
+
+impl<TIM, H1, H2, H3> HallSensor<TIM, H1, H2, H3>
+where
+    TIM: TimerWithPwm1Ch + TimerWithInputCapture,
+    H1: InputPin,
+    H2: InputPin,
+    H3: InputPin,
+{
+    pub fn new(tim: TIM, clk: Hertz, pins: (H1, H2, H3)) -> Self {
+        Self { tim, clk, pins }
+    }
+
+    pub fn start(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Update | Event::C1);
+        self.tim.reset_counter();
+        self.tim.enable_counter();
+    }
+
+    pub fn stop(&mut self) {
+        self.tim.disable_counter();
+    }
+
+    /// The measured intervals are in ticks of this frequency.
+    #[inline]
+    pub fn get_tick_freq(&self) -> Hertz {
+        self.clk / (self.tim.read_prescaler() as u32 + 1)
+    }
+
+    /// The levels of the inputs, H1 is bit 0.
+    pub fn state(&mut self) -> u8 {
+        let h1 = self.pins.0.is_high().unwrap_or(false) as u8;
+        let h2 = self.pins.1.is_high().unwrap_or(false) as u8;
+        let h3 = self.pins.2.is_high().unwrap_or(false) as u8;
+        h1 | (h2 << 1) | (h3 << 2)
+    }
+
+    /// The sector from 0 to 5, or `None` if the state is invalid.
+    pub fn sector(&mut self) -> Option<u8> {
+        SECTORS[self.state() as usize]
+    }
+
+    /// Returns the time between the last two edges in ticks.
+    ///
+    /// It's `None` before the first interval is measured, or if the motor stopped or is slower
+    /// than a counter period per edge.
+    pub fn read_interval(&mut self) -> Option<u32> {
+        let flags = self.tim.get_interrupt_flag();
+        if flags.contains(Event::Update) {
+            if flags.contains(Event::C1) {
+                // The counter overflowed during this interval, discard it. The next one is
+                // measured from this edge.
+                self.tim.get_ch1_cc_value();
+                self.tim.clear_interrupt_flag(Event::Update);
+            }
+            return None;
+        }
+        // Reading the value clears the capture flag.
+        let interval = self.tim.get_ch1_cc_value();
+        (interval > 0).then_some(interval)
+    }
+
+    /// The mechanical speed of a motor with `pole_pairs`, 6 edges per electrical revolution.
+    pub fn read_rpm(&mut self, pole_pairs: u32) -> Option<f32> {
+        let interval = self.read_interval()?;
+        let edge_freq = self.get_tick_freq().raw() as f32 / interval as f32;
+        Some(edge_freq * 60.0 / (6 * pole_pairs) as f32)
+    }
+
+    pub fn release(self) -> (TIM, (H1, H2, H3)) {
+        (self.tim, self.pins)
+    }
+}
@@ -1,5 +1,21 @@
 pub mod pwm;
 pub use pwm::*;
+pub mod capture;
+pub use capture::*;
+pub mod pwm_input;
+pub use pwm_input::*;
+pub mod qei;
+pub use qei::*;
+pub mod hall;
+pub use hall::*;
+pub mod one_pulse;
+pub use one_pulse::*;
+pub mod chain;
+pub use chain::*;
+pub mod pulse_counter;
+pub use pulse_counter::*;
+pub mod pwm_dma;
+pub use pwm_dma::*;
 pub mod counter;
 pub use counter::*;
 pub mod fix_timer;
@@ -12,6 +28,21 @@ pub trait PwmChannel: embedded_hal::pwm::SetDutyCycle {
     fn set_enable(&mut self, en: bool);
 }
 
+pub trait ComplementaryPwmChannel: PwmChannel {
+    fn config_complementary(&mut self, polarity: PwmPolarity);
+    fn set_complementary_enable(&mut self, en: bool);
+}
+
+pub trait CaptureChannel {
+    fn config(&mut self, edge: CaptureEdge, prescaler: CapturePrescaler, filter: InputFilter);
+    fn set_enable(&mut self, en: bool);
+    /// Returns the counter value of the latest capture, or `None` if nothing is captured since
+    /// the last read.
+    fn read_capture(&mut self) -> Option<u32>;
+    /// Returns `true` once if a capture was lost because the previous one was not read.
+    fn is_overcaptured(&mut self) -> bool;
+}
+
 // ----------------------------------------------------------------------------
 
 pub trait GeneralTimer {
@@ -32,6 +63,9 @@ pub trait GeneralTimer {
     fn read_prescaler(&self) -> u16;
     fn read_count(&self) -> u32;
     fn trigger_update(&mut self);
+    /// Only the counter overflow generates an update interrupt, not the UG bit or the slave
+    /// mode controller.
+    fn set_update_on_overflow_only(&mut self, b: bool);
     fn stop_in_debug(&mut self, state: bool);
     fn config_freq(&mut self, clock: Hertz, update_freq: Hertz);
 
@@ -39,16 +73,36 @@ pub trait GeneralTimer {
     fn listen_interrupt(&mut self, event: Event, b: bool);
     fn get_interrupt_flag(&self) -> Event;
     fn start_one_pulse(&mut self);
+    /// The counter stops at the next update event.
+    fn set_one_pulse_mode(&mut self, b: bool);
 }
 
 pub trait TimerDirection: GeneralTimer {
     fn set_count_direction(&mut self, dir: CountDirection);
+    fn get_count_direction(&self) -> CountDirection;
+    /// It can't be changed from edge-aligned to center-aligned while the counter is enabled.
+    fn set_align_mode(&mut self, mode: AlignMode);
 }
 
 pub trait MasterTimer: GeneralTimer {
     fn master_mode(&mut self, mode: MasterMode);
 }
 
+pub trait SlaveTimer: GeneralTimer {
+    fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource);
+    /// Connect the XOR of the inputs of channel 1 to 3 to TI1.
+    fn set_xor_input(&mut self, en: bool);
+    /// The external trigger input (ETR), `Falling` inverts it.
+    fn config_external_trigger(
+        &mut self,
+        edge: CaptureEdge,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    );
+    /// The counter is clocked by ETR.
+    fn set_external_clock_mode2(&mut self, en: bool);
+}
+
 pub trait TimerWithPwm: GeneralTimer {
     fn start_pwm(&mut self);
     fn stop_pwm(&mut self);
@@ -57,6 +111,59 @@ pub trait TimerWithPwm: GeneralTimer {
     fn set_polarity(&mut self, channel: Channel, polarity: PwmPolarity);
 }
 
+pub trait TimerWithInputCapture: GeneralTimer {
+    /// The channel must be disabled.
+    fn config_input_capture(
+        &mut self,
+        channel: Channel,
+        input: CaptureInput,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    );
+    fn set_capture_edge(&mut self, channel: Channel, edge: CaptureEdge);
+    fn is_overcaptured(&self, channel: Channel) -> bool;
+    fn clear_overcapture(&mut self, channel: Channel);
+}
+
+/// Advanced-control timers with complementary outputs, dead-time insertion and break input.
+pub trait TimerWithComplementaryPwm: TimerWithPwm {
+    fn enable_complementary(&mut self, channel: Channel, en: bool);
+    fn set_complementary_polarity(&mut self, channel: Channel, polarity: PwmPolarity);
+    /// Output levels when the main output is disabled, after a dead time.
+    fn set_idle_state(&mut self, channel: Channel, output: bool, complementary: bool);
+    /// DTG bits, see [dead_time_bits].
+    fn set_dead_time_bits(&mut self, dtg: u8);
+    /// `None` disables the break input.
+    fn set_break(&mut self, polarity: Option<BreakPolarity>);
+    /// The main output is enabled again at the next update event after a break.
+    fn set_automatic_output(&mut self, en: bool);
+    /// Off-state selection for the run mode (OSSR) and the idle mode (OSSI).
+    /// When it's `true`, a disabled output is driven to its inactive level instead of being
+    /// released.
+    fn set_off_state(&mut self, run: bool, idle: bool);
+    fn enable_main_output(&mut self, en: bool);
+    fn is_main_output_enabled(&self) -> bool;
+    /// The update event is generated every `rep + 1` counter periods.
+    fn set_repetition_counter(&mut self, rep: u8);
+    /// Preload CCxE, CCxNE and OCxM, they are transferred at the commutation event.
+    /// `None` disables the preload.
+    fn set_commutation(&mut self, update: Option<CommutationUpdate>);
+    /// Generate a commutation event by software.
+    fn trigger_commutation(&mut self);
+}
+
+pub trait TimerWithDma: GeneralTimer {
+    /// `Update`, `C1` to `C4` and `Com` request the DMA, `Com` only on TIM1 and TIM8.
+    fn listen_dma_request(&mut self, event: Event, b: bool);
+    /// Transfer `len` registers from the register at `base` (in words from CR1) through DMAR.
+    /// `len` is 1 to 18, the registers must be before DCR.
+    fn config_dma_burst(&mut self, base: u8, len: u8);
+    /// Address of DMAR
+    fn dma_burst_address(&self) -> usize;
+    /// Address of CCR of the channel
+    fn cc_address(&self, channel: Channel) -> usize;
+}
+
 pub trait TimerWithPwm1Ch: TimerWithPwm {
     fn enable_ch1(&mut self, en: bool);
     fn set_ch1_cc_value(&mut self, value: u32);
@@ -81,6 +188,38 @@ pub trait TimerWithPwm4Ch: TimerWithPwm3Ch {
     fn get_ch4_cc_value(&self) -> u32;
 }
 
+// Utilities ------------------------------------------------------------------
+
+/// Encode a dead time into the DTG bits of BDTR, the dead time is rounded up.
+/// `clk` is the timer clock. Returns `None` if it's longer than 1008 clock cycles.
+pub fn dead_time_bits(clk: Hertz, ns: u32) -> Option<u8> {
+    let ticks = (ns as u64 * clk.raw() as u64).div_ceil(1_000_000_000);
+    match ticks {
+        0..=127 => Some(ticks as u8),
+        128..=254 => Some(0x80 | (ticks.div_ceil(2) - 64) as u8),
+        255..=504 => Some(0xC0 | (ticks.div_ceil(8) - 32) as u8),
+        505..=1008 => Some(0xE0 | (ticks.div_ceil(16) - 32) as u8),
+        _ => None,
+    }
+}
+
+/// Read the counter between two readings of the update flag, the counter is read again if it
+/// overflowed during the first reading. Returns the counter and whether an overflow is not
+/// handled by the interrupt yet.
+fn read_count_and_overflow(
+    mut overflowed: impl FnMut() -> bool,
+    mut count: impl FnMut() -> u32,
+) -> (u32, bool) {
+    let before = overflowed();
+    let value = count();
+    let after = overflowed();
+    if before != after {
+        (count(), after)
+    } else {
+        (value, after)
+    }
+}
+
 // Enumerate ------------------------------------------------------------------
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -97,6 +236,47 @@ pub enum CountDirection {
     Down,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlignMode {
+    /// Counts up or down depending on the direction.
+    Edge = 0,
+    /// Counts up and down alternatively, the output compare flags are set when counting down.
+    Center1 = 1,
+    /// The output compare flags are set when counting up.
+    Center2 = 2,
+    /// The output compare flags are set when counting up and down.
+    Center3 = 3,
+}
+
+/// The source of the commutation event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommutationUpdate {
+    /// Only [TimerWithComplementaryPwm::trigger_commutation]
+    Software,
+    /// By software or a rising edge of the trigger input (TRGI)
+    SoftwareOrTrigger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QeiMode {
+    /// Counts on the edges of TI2, 2 counts per cycle
+    Mode1,
+    /// Counts on the edges of TI1, 2 counts per cycle
+    Mode2,
+    /// Counts on the edges of both inputs, 4 counts per cycle
+    Mode3,
+}
+
+impl From<QeiMode> for SlaveMode {
+    fn from(value: QeiMode) -> Self {
+        match value {
+            QeiMode::Mode1 => SlaveMode::EncoderMode1,
+            QeiMode::Mode2 => SlaveMode::EncoderMode2,
+            QeiMode::Mode3 => SlaveMode::EncoderMode3,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PwmMode {
     Mode1,
@@ -109,6 +289,63 @@ pub enum PwmPolarity {
     ActiveLow,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+/// The signal that the capture channel samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CaptureInput {
+    /// The input of the channel itself, e.g. TI1 for channel 1
+    Direct = 1,
+    /// The input of the paired channel, e.g. TI2 for channel 1 and TI1 for channel 2
+    Indirect = 2,
+    /// The trigger input selected by the slave mode controller
+    Trc = 3,
+}
+
+/// Both edges are not supported by the timers of STM32F1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureEdge {
+    Rising,
+    Falling,
+}
+
+/// Capture once every N events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CapturePrescaler {
+    Div1 = 0,
+    Div2 = 1,
+    Div4 = 2,
+    Div8 = 3,
+}
+
+/// Sampling frequency and the number of consecutive samples needed to validate a transition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InputFilter {
+    NoFilter = 0,
+    FckIntN2 = 1,
+    FckIntN4 = 2,
+    FckIntN8 = 3,
+    FdtsDiv2N6 = 4,
+    FdtsDiv2N8 = 5,
+    FdtsDiv4N6 = 6,
+    FdtsDiv4N8 = 7,
+    FdtsDiv8N6 = 8,
+    FdtsDiv8N8 = 9,
+    FdtsDiv16N5 = 10,
+    FdtsDiv16N6 = 11,
+    FdtsDiv16N8 = 12,
+    FdtsDiv32N5 = 13,
+    FdtsDiv32N6 = 14,
+    FdtsDiv32N8 = 15,
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum Error {
     /// Timer is disabled
@@ -130,6 +367,8 @@ bitflags::bitflags! {
         const C2 = 1 << 2;
         const C3 = 1 << 3;
         const C4 = 1 << 4;
+        const Com = 1 << 5;
+        const Break = 1 << 7;
     }
 }
 
@@ -152,3 +391,88 @@ pub enum MasterMode {
     ///7: OC4REF signal is used as trigger output
     CompareOc4,
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SlaveMode {
+    /// The counter is clocked by the internal clock.
+    Disabled = 0,
+    /// Counts on TI2FP1 edges depending on the level of TI1FP2
+    EncoderMode1 = 1,
+    /// Counts on TI1FP2 edges depending on the level of TI2FP1
+    EncoderMode2 = 2,
+    /// Counts on both TI1FP1 and TI2FP2 edges depending on the level of the other input
+    EncoderMode3 = 3,
+    /// A rising edge of the trigger input reinitializes the counter.
+    Reset = 4,
+    /// The counter is enabled while the trigger input is high.
+    Gated = 5,
+    /// The counter starts at a rising edge of the trigger input.
+    Trigger = 6,
+    /// Rising edges of the trigger input clock the counter.
+    ExternalClock1 = 7,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TriggerSource {
+    /// Internal trigger 0
+    Itr0 = 0,
+    /// Internal trigger 1
+    Itr1 = 1,
+    /// Internal trigger 2
+    Itr2 = 2,
+    /// Internal trigger 3
+    Itr3 = 3,
+    /// TI1 edge detector
+    Ti1FEd = 4,
+    /// Filtered timer input 1
+    Ti1Fp1 = 5,
+    /// Filtered timer input 2
+    Ti2Fp2 = 6,
+    /// External trigger input
+    Etrf = 7,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Each access to the timer takes one tick, the counter moves by `step` per tick from
+    /// `start` and sets the update flag when it wraps. Returns the result of `read` and the
+    /// position after it.
+    pub(super) fn simulate_ticks<R>(
+        start: i64,
+        step: i64,
+        max: u32,
+        read: impl FnOnce(&dyn Fn() -> bool, &dyn Fn() -> u32) -> R,
+    ) -> (R, i64) {
+        let tick = Cell::new(0);
+        let position = || {
+            let t = tick.get();
+            tick.set(t + 1);
+            start + t * step
+        };
+        let overflowed = || !(0..=max as i64).contains(&position());
+        let count = || position().rem_euclid(max as i64 + 1) as u32;
+        let result = read(&overflowed, &count);
+        (result, start + tick.get() * step)
+    }
+
+    #[test]
+    fn dead_time() {
+        let clk = Hertz::MHz(72);
+        assert_eq!(dead_time_bits(clk, 0), Some(0));
+        // 13.9 ns per tick
+        assert_eq!(dead_time_bits(clk, 100), Some(8));
+        assert_eq!(dead_time_bits(clk, 1_763), Some(127));
+        // (64 + 1) * 2 ticks
+        assert_eq!(dead_time_bits(clk, 1_780), Some(0x81));
+        // (32 + 0) * 8 ticks
+        assert_eq!(dead_time_bits(clk, 3_540), Some(0xC0));
+        // (32 + 31) * 16 ticks
+        assert_eq!(dead_time_bits(clk, 14_000), Some(0xFF));
+        assert_eq!(dead_time_bits(clk, 14_100), None);
+    }
+}
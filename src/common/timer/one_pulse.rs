@@ -0,0 +1,114 @@
+use super::*;
+use fugit::MicrosDurationU32;
+
+/// A single pulse after a delay, started by software or a trigger input.
+///
+/// The output channel is in PWM mode 2 with the one-pulse mode, so it's inactive until the
+/// counter reaches the delay, then active until the counter stops at the end of the pulse.
+///
+/// The output of TIM1 and TIM8 stays inactive until
+/// [enable_main_output](Self::enable_main_output) is called.
+///
+/// ```ignore
+/// let mut pulse = dp.TIM3.constrain(&mut mcu).into_one_pulse(pin, 1000.micros(), &mut mcu);
+/// pulse.set_pulse(100.micros(), 10.micros()).unwrap();
+/// pulse.trigger();
+/// ```
+pub struct OnePulse<TIM> {
+    pub(crate) tim: TIM,
+    clk: Hertz,
+    channel: Channel,
+}
+
+impl<TIM: TimerWithPwm2Ch> OnePulse<TIM> {
+    /// `channel` must be configured in PWM mode 2, it's [Channel::C1] or [Channel::C2].
+    pub fn new(mut tim: TIM, clk: Hertz, channel: Channel) -> Self {
+        tim.enable_preload(true);
+        tim.set_one_pulse_mode(true);
+        Self { tim, clk, channel }
+    }
+
+    /// The delay and the width are in ticks of this frequency.
+    #[inline]
+    pub fn get_tick_freq(&self) -> Hertz {
+        self.clk / (self.tim.read_prescaler() as u32 + 1)
+    }
+
+    /// The delay is at least one tick. It's used by the next pulse if a pulse is running.
+    pub fn set_pulse(
+        &mut self,
+        delay: MicrosDurationU32,
+        width: MicrosDurationU32,
+    ) -> Result<(), Error> {
+        let delay = self.to_ticks(delay).max(1);
+        let width = self.to_ticks(width).max(1);
+        self.set_pulse_ticks(delay, width)
+    }
+
+    /// The delay and the width are at least one tick, otherwise it returns
+    /// `Error::WrongAutoReload`.
+    pub fn set_pulse_ticks(&mut self, delay: u32, width: u32) -> Result<(), Error> {
+        if delay == 0 || width == 0 {
+            return Err(Error::WrongAutoReload);
+        }
+        let arr = delay
+            .checked_add(width - 1)
+            .ok_or(Error::WrongAutoReload)?;
+        self.tim.set_auto_reload(arr)?;
+        match self.channel {
+            Channel::C1 => self.tim.set_ch1_cc_value(delay),
+            _ => self.tim.set_ch2_cc_value(delay),
+        }
+        if !self.tim.is_counter_enabled() {
+            // Load the values now, otherwise they're loaded at the end of the pulse.
+            self.tim.trigger_update();
+        }
+        Ok(())
+    }
+
+    /// Start a pulse by software. The main output of TIM1 and TIM8 is not enabled by it.
+    pub fn trigger(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.tim.enable_counter();
+    }
+
+    /// Whether a pulse is waiting for its delay or being output.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.tim.is_counter_enabled()
+    }
+
+    /// Stop the pulse and reset the output.
+    pub fn cancel(&mut self) {
+        self.tim.disable_counter();
+        self.tim.reset_counter();
+    }
+
+    pub fn release(self) -> TIM {
+        self.tim
+    }
+
+    fn to_ticks(&self, duration: MicrosDurationU32) -> u32 {
+        let ticks = duration.ticks() as u64 * self.get_tick_freq().raw() as u64 / 1_000_000;
+        ticks.min(u32::MAX as u64) as u32
+    }
+}
+
+impl<TIM: TimerWithPwm2Ch + TimerWithComplementaryPwm> OnePulse<TIM> {
+    pub fn enable_main_output(&mut self) {
+        self.tim.enable_main_output(true);
+    }
+}
+
+impl<TIM: TimerWithPwm2Ch + SlaveTimer> OnePulse<TIM> {
+    /// Start a pulse at each rising edge of `trigger`, such as `Etrf` or the TRGO of another timer.
+    /// `None` only allows [Self::trigger].
+    pub fn set_trigger(&mut self, trigger: Option<TriggerSource>) {
+        match trigger {
+            Some(t) => self.tim.set_slave_mode(SlaveMode::Trigger, t),
+            None => self
+                .tim
+                .set_slave_mode(SlaveMode::Disabled, TriggerSource::Itr0),
+        }
+    }
+}
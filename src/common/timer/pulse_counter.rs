@@ -0,0 +1,111 @@
+use super::*;
+
+/// Counts the external pulses on ETR, the counter is clocked in the external clock mode 2.
+///
+/// The count is extended beyond the 16-bit counter by counting the overflows, call
+/// [`on_interrupt`](Self::on_interrupt) in the update interrupt after
+/// [`listen_overflow`](Self::listen_overflow).
+///
+/// ```ignore
+/// let mut counter = dp.TIM2.constrain(&mut mcu).into_pulse_counter(
+///     pa0.into_floating_input(),
+///     CaptureEdge::Rising,
+///     CapturePrescaler::Div1,
+///     InputFilter::FckIntN8,
+///     &mut mcu,
+/// );
+/// counter.listen_overflow();
+/// counter.start();
+/// let pulses = counter.total();
+/// ```
+pub struct PulseCounter<TIM> {
+    tim: TIM,
+    overflows: u64,
+}
+
+impl<TIM: SlaveTimer> PulseCounter<TIM> {
+    pub fn new(tim: TIM) -> Self {
+        Self { tim, overflows: 0 }
+    }
+
+    pub fn start(&mut self) {
+        self.tim.enable_counter();
+    }
+
+    pub fn stop(&mut self) {
+        self.tim.disable_counter();
+    }
+
+    /// The raw counter value
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.tim.read_count()
+    }
+
+    /// Set the count to 0.
+    pub fn reset(&mut self) {
+        self.tim.reset_counter();
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.overflows = 0;
+    }
+
+    pub fn listen_overflow(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.tim.listen_interrupt(Event::Update, true);
+    }
+
+    pub fn unlisten_overflow(&mut self) {
+        self.tim.listen_interrupt(Event::Update, false);
+    }
+
+    /// Call it in the update interrupt to count the overflows.
+    pub fn on_interrupt(&mut self) {
+        if self.tim.get_interrupt_flag().contains(Event::Update) {
+            self.tim.clear_interrupt_flag(Event::Update);
+            self.overflows += 1;
+        }
+    }
+
+    /// The count extended by the overflows
+    pub fn total(&self) -> u64 {
+        extend_total(
+            self.overflows,
+            TIM::max_auto_reload(),
+            || self.tim.get_interrupt_flag().contains(Event::Update),
+            || self.count(),
+        )
+    }
+
+    pub fn release(self) -> TIM {
+        self.tim
+    }
+}
+
+/// Adds the overflow not handled by the interrupt yet, the counter only counts up.
+fn extend_total(
+    overflows: u64,
+    max: u32,
+    overflowed: impl FnMut() -> bool,
+    count: impl FnMut() -> u32,
+) -> u64 {
+    let (value, pending) = read_count_and_overflow(overflowed, count);
+    (overflows + pending as u64) * (max as u64 + 1) + value as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::timer::tests::simulate_ticks;
+
+    const MAX: u32 = 0xFFFF;
+
+    #[test]
+    fn total_at_overflow() {
+        for start in MAX as i64 - 3..=MAX as i64 + 1 {
+            let (total, end) = simulate_ticks(start, 1, MAX, |overflowed, count| {
+                extend_total(0, MAX, overflowed, count)
+            });
+            assert!((start..=end).contains(&(total as i64)), "{start}: {total}");
+        }
+    }
+}
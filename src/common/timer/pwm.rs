@@ -3,7 +3,7 @@ use core::convert::Infallible;
 use embedded_hal::pwm::{ErrorType, SetDutyCycle};
 
 pub struct PwmTimer<TIM> {
-    tim: TIM,
+    pub(crate) tim: TIM,
     clk: Hertz,
 }
 impl<TIM: TimerWithPwm> PwmTimer<TIM> {
@@ -37,6 +37,97 @@ impl<TIM: TimerWithPwm> PwmTimer<TIM> {
     }
 }
 
+impl<TIM: TimerWithComplementaryPwm> PwmTimer<TIM> {
+    /// Dead time inserted before an output or its complementary output turns active.
+    pub fn set_dead_time(&mut self, ns: u32) {
+        let bits = dead_time_bits(self.clk, ns).expect("dead time too long");
+        self.tim.set_dead_time_bits(bits);
+    }
+
+    pub fn disable_break(&mut self) {
+        self.tim.set_break(None);
+    }
+
+    #[inline]
+    pub fn is_break_active(&self) -> bool {
+        self.tim.get_interrupt_flag().contains(Event::Break)
+    }
+
+    /// Enable the main output again after a break.
+    pub fn clear_break(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Break);
+        self.tim.enable_main_output(true);
+    }
+
+    /// The main output is enabled again at the next update event after a break.
+    pub fn set_automatic_output(&mut self, en: bool) {
+        self.tim.set_automatic_output(en);
+    }
+
+    /// See [TimerWithComplementaryPwm::set_off_state].
+    pub fn set_off_state(&mut self, run: bool, idle: bool) {
+        self.tim.set_off_state(run, idle);
+    }
+
+    /// Output levels of the channel when the main output is disabled.
+    pub fn set_idle_state(&mut self, channel: Channel, output: bool, complementary: bool) {
+        self.tim.set_idle_state(channel, output, complementary);
+    }
+
+    /// All the outputs go to the idle state.
+    pub fn disable_main_output(&mut self) {
+        self.tim.enable_main_output(false);
+    }
+
+    pub fn enable_main_output(&mut self) {
+        self.tim.enable_main_output(true);
+    }
+
+    #[inline]
+    pub fn is_main_output_enabled(&self) -> bool {
+        self.tim.is_main_output_enabled()
+    }
+
+    /// The update event is generated every `rep + 1` PWM periods, or half periods in
+    /// center-aligned mode.
+    pub fn set_repetition_counter(&mut self, rep: u8) {
+        self.tim.set_repetition_counter(rep);
+    }
+
+    /// The enable bits and the modes of the channels are preloaded, so the next step of a
+    /// six-step sequence can be loaded ahead and switched at once by [Self::commutate].
+    pub fn enable_commutation(&mut self) {
+        self.tim.set_commutation(Some(CommutationUpdate::Software));
+    }
+
+    pub fn disable_commutation(&mut self) {
+        self.tim.set_commutation(None);
+    }
+
+    /// Switch to the preloaded step.
+    #[inline]
+    pub fn commutate(&mut self) {
+        self.tim.trigger_commutation();
+    }
+}
+
+impl<TIM: TimerWithComplementaryPwm + SlaveTimer> PwmTimer<TIM> {
+    /// Like [Self::enable_commutation], but the steps are also switched by a rising edge of
+    /// `trigger`, such as the TRGO of a Hall sensor timer.
+    pub fn enable_commutation_on_trigger(&mut self, trigger: TriggerSource) {
+        self.tim.set_slave_mode(SlaveMode::Disabled, trigger);
+        self.tim
+            .set_commutation(Some(CommutationUpdate::SoftwareOrTrigger));
+    }
+}
+
+impl<TIM: TimerWithPwm + TimerDirection> PwmTimer<TIM> {
+    /// Call it before [Self::start]. The PWM frequency is halved in the center-aligned modes.
+    pub fn set_align_mode(&mut self, mode: AlignMode) {
+        self.tim.set_align_mode(mode);
+    }
+}
+
 // Channels -------------------------------------------------------------------
 
 macro_rules! pwm_channel {
@@ -86,3 +177,22 @@ pwm_channel!(PwmChannel1, TimerWithPwm1Ch, Channel::C1, enable_ch1);
 pwm_channel!(PwmChannel2, TimerWithPwm2Ch, Channel::C2, enable_ch2);
 pwm_channel!(PwmChannel3, TimerWithPwm3Ch, Channel::C3, enable_ch3);
 pwm_channel!(PwmChannel4, TimerWithPwm4Ch, Channel::C4, enable_ch4);
+
+macro_rules! complementary_channel {
+    ($name:ident, $Tim:ident, $ch:expr) => {
+        impl<TIM: $Tim + TimerWithComplementaryPwm> ComplementaryPwmChannel for $name<TIM> {
+            #[inline(always)]
+            fn config_complementary(&mut self, polarity: PwmPolarity) {
+                self.tim.set_complementary_polarity($ch, polarity);
+            }
+
+            #[inline(always)]
+            fn set_complementary_enable(&mut self, en: bool) {
+                self.tim.enable_complementary($ch, en);
+            }
+        }
+    };
+}
+complementary_channel!(PwmChannel1, TimerWithPwm1Ch, Channel::C1);
+complementary_channel!(PwmChannel2, TimerWithPwm2Ch, Channel::C2);
+complementary_channel!(PwmChannel3, TimerWithPwm3Ch, Channel::C3);
@@ -0,0 +1,94 @@
+use super::*;
+use crate::common::dma::DmaChannel;
+use core::convert::Infallible;
+
+/// Index of CCR1 in words from CR1, for the DMA burst
+const CCR1_INDEX: u8 = 13;
+
+/// Streams duty values into the compare registers by a DMA channel, one value at each update
+/// event of the timer.
+///
+/// The compare registers are preloaded, so a value is output in the PWM period after the one it's
+/// transferred in. For WS2812 LEDs, the buffer has a duty for each bit followed by zeros for the
+/// reset code.
+///
+/// ```ignore
+/// let mut pwm_dma = pwm.pwm_dma(dma1.2, Channel::C1);
+/// pwm_dma.write(&duties);
+/// ```
+pub struct PwmDma<TIM, CH> {
+    tim: TIM,
+    ch: CH,
+    channel: Channel,
+}
+
+impl<TIM, CH> PwmDma<TIM, CH>
+where
+    TIM: TimerWithPwm + TimerWithDma,
+    CH: DmaChannel,
+{
+    /// `ch` must be connected to the update request of the timer.
+    pub fn new(tim: TIM, ch: CH, channel: Channel) -> Self {
+        Self { tim, ch, channel }
+    }
+
+    pub fn release(mut self) -> CH {
+        self.cancel();
+        self.ch
+    }
+
+    /// Output `duties` on the channel and wait until it's done.
+    pub fn write(&mut self, duties: &[u16]) {
+        unsafe { self.start(duties, false) };
+        nb::block!(self.poll()).ok();
+    }
+
+    /// Start streaming `duties` into the channel, then call [poll](Self::poll) until it's done.
+    /// A `circular` transfer repeats until it's canceled.
+    ///
+    /// # Safety
+    ///
+    /// `duties` must stay valid until the transfer is done or canceled.
+    pub unsafe fn start(&mut self, duties: &[u16], circular: bool) {
+        let address = self.tim.cc_address(self.channel);
+        self.start_transfer(address, duties, circular);
+    }
+
+    /// Update the compare registers of `count` channels from `first` at each update event, by
+    /// the DMA burst. `duties` holds the values of the channels in turn.
+    ///
+    /// # Safety
+    ///
+    /// `duties` must stay valid until the transfer is done or canceled.
+    pub unsafe fn start_burst(&mut self, first: Channel, count: u8, duties: &[u16], circular: bool) {
+        assert!(count >= 1 && first as u8 + count <= 4);
+        assert!(duties.len().is_multiple_of(count as usize));
+        self.tim
+            .config_dma_burst(CCR1_INDEX + first as u8, count);
+        let address = self.tim.dma_burst_address();
+        self.start_transfer(address, duties, circular);
+    }
+
+    /// Returns `Ok` when the whole transfer is done.
+    pub fn poll(&mut self) -> nb::Result<(), Infallible> {
+        if self.ch.in_progress() {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.cancel();
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) {
+        self.tim.listen_dma_request(Event::Update, false);
+        self.ch.stop();
+    }
+
+    fn start_transfer(&mut self, address: usize, duties: &[u16], circular: bool) {
+        self.cancel();
+        self.ch
+            .set_peripheral_address::<u16>(address, true, false, circular);
+        self.ch.set_memory_buf_for_peripheral(duties);
+        self.ch.start();
+        self.tim.listen_dma_request(Event::Update, true);
+    }
+}
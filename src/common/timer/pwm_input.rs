@@ -0,0 +1,67 @@
+use super::*;
+
+/// Measures the frequency and the duty cycle of a signal on channel 1.
+///
+/// Channel 1 captures the period at the rising edges, which also reset the counter by the slave
+/// mode controller. Channel 2 captures the pulse width at the falling edges.
+pub struct PwmInput<TIM> {
+    tim: TIM,
+    clk: Hertz,
+}
+
+impl<TIM: TimerWithPwm2Ch + TimerWithInputCapture> PwmInput<TIM> {
+    pub fn new(tim: TIM, clk: Hertz) -> Self {
+        Self { tim, clk }
+    }
+
+    pub fn start(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.tim.reset_counter();
+        self.tim.enable_counter();
+    }
+
+    pub fn stop(&mut self) {
+        self.tim.disable_counter();
+    }
+
+    /// The measured values are in ticks of this frequency.
+    #[inline]
+    pub fn get_tick_freq(&self) -> Hertz {
+        self.clk / (self.tim.read_prescaler() as u32 + 1)
+    }
+
+    /// Returns the period and the pulse width in ticks.
+    ///
+    /// It's `None` before the first period is measured, or if the signal stopped or is slower than
+    /// the expected frequency range.
+    pub fn read_ticks(&mut self) -> Option<(u32, u32)> {
+        if self.tim.get_interrupt_flag().contains(Event::C1) {
+            // A new period is measured after the counter overflowed.
+            self.tim.clear_interrupt_flag(Event::Update);
+        }
+        if self.tim.get_interrupt_flag().contains(Event::Update) {
+            return None;
+        }
+        // Reading the values clears the capture flags. A period captured between the two reads
+        // doesn't match the pulse width, read both again.
+        let (period, pulse) = loop {
+            let period = self.tim.get_ch1_cc_value();
+            let pulse = self.tim.get_ch2_cc_value();
+            if !self.tim.get_interrupt_flag().contains(Event::C1) {
+                break (period, pulse);
+            }
+        };
+        (period > 0).then_some((period, pulse))
+    }
+
+    pub fn read_frequency(&mut self) -> Option<Hertz> {
+        let (period, _) = self.read_ticks()?;
+        Some(self.get_tick_freq() / period)
+    }
+
+    /// The ratio of the pulse width to the period, from 0.0 to 1.0
+    pub fn read_duty_cycle(&mut self) -> Option<f32> {
+        let (period, pulse) = self.read_ticks()?;
+        Some(pulse as f32 / period as f32)
+    }
+}
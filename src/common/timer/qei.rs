@@ -0,0 +1,145 @@
+use super::*;
+
+/// Quadrature encoder interface
+///
+/// The counter counts up or down on the edges of the two inputs. The position is extended beyond
+/// the 16-bit counter by counting the overflows, call [`on_interrupt`](Self::on_interrupt) in the
+/// update interrupt after [`listen_overflow`](Self::listen_overflow).
+///
+/// ```ignore
+/// let mut qei = dp.TIM2.constrain(&mut mcu).into_qei(
+///     (pa0.into_floating_input(), pa1.into_floating_input()),
+///     QeiMode::Mode3,
+///     InputFilter::FckIntN8,
+///     &mut mcu,
+/// );
+/// qei.listen_overflow();
+/// let position = qei.position();
+/// ```
+pub struct Qei<TIM> {
+    tim: TIM,
+    overflows: i64,
+}
+
+impl<TIM: TimerDirection + TimerWithInputCapture> Qei<TIM> {
+    pub fn new(tim: TIM) -> Self {
+        Self { tim, overflows: 0 }
+    }
+
+    /// The raw counter value
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.tim.read_count()
+    }
+
+    /// The direction of the latest count
+    #[inline]
+    pub fn direction(&self) -> CountDirection {
+        self.tim.get_count_direction()
+    }
+
+    /// Invert the inputs, inverting one of them reverses the counting direction.
+    pub fn set_inverted(&mut self, ti1: bool, ti2: bool) {
+        let edge = |inverted| {
+            if inverted {
+                CaptureEdge::Falling
+            } else {
+                CaptureEdge::Rising
+            }
+        };
+        self.tim.set_capture_edge(Channel::C1, edge(ti1));
+        self.tim.set_capture_edge(Channel::C2, edge(ti2));
+    }
+
+    /// Set the position to 0.
+    pub fn reset(&mut self) {
+        self.tim.reset_counter();
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.overflows = 0;
+    }
+
+    pub fn listen_overflow(&mut self) {
+        self.tim.clear_interrupt_flag(Event::Update);
+        self.tim.listen_interrupt(Event::Update, true);
+    }
+
+    pub fn unlisten_overflow(&mut self) {
+        self.tim.listen_interrupt(Event::Update, false);
+    }
+
+    /// Call it in the update interrupt to count the overflows.
+    pub fn on_interrupt(&mut self) {
+        if self.tim.get_interrupt_flag().contains(Event::Update) {
+            self.tim.clear_interrupt_flag(Event::Update);
+            self.overflows += wrap_direction(self.count(), TIM::max_auto_reload());
+        }
+    }
+
+    /// The position extended by the overflows
+    pub fn position(&self) -> i64 {
+        extend_position(
+            self.overflows,
+            TIM::max_auto_reload(),
+            || self.tim.get_interrupt_flag().contains(Event::Update),
+            || self.count(),
+        )
+    }
+
+    /// The position wraps around at the limits of `i32`.
+    #[inline]
+    pub fn position_i32(&self) -> i32 {
+        self.position() as i32
+    }
+
+    pub fn release(self) -> TIM {
+        self.tim
+    }
+}
+
+/// Adds the overflow not handled by the interrupt yet, in the counting direction.
+fn extend_position(
+    overflows: i64,
+    max: u32,
+    overflowed: impl FnMut() -> bool,
+    count: impl FnMut() -> u32,
+) -> i64 {
+    let (value, pending) = read_count_and_overflow(overflowed, count);
+    let overflows = if pending {
+        overflows + wrap_direction(value, max)
+    } else {
+        overflows
+    };
+    overflows * (max as i64 + 1) + value as i64
+}
+
+/// The counter is near 0 just after counting up over the maximum value, and near the maximum
+/// value just after counting down below 0.
+fn wrap_direction(count: u32, max: u32) -> i64 {
+    if count < max / 2 { 1 } else { -1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::timer::tests::simulate_ticks;
+
+    const MAX: u32 = 0xFFFF;
+
+    fn simulate(start: i64, step: i64) -> (i64, i64) {
+        simulate_ticks(start, step, MAX, |overflowed, count| {
+            extend_position(0, MAX, overflowed, count)
+        })
+    }
+
+    #[test]
+    fn position_at_overflow() {
+        for start in MAX as i64 - 3..=MAX as i64 + 1 {
+            let (position, end) = simulate(start, 1);
+            assert!((start..=end).contains(&position), "{start}: {position}");
+        }
+        for start in -1..=3 {
+            let (position, end) = simulate(start, -1);
+            assert!((end..=start).contains(&position), "{start}: {position}");
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! XMODEM-CRC and YMODEM receiver
+//!
+//! The packets are written to a [`FlashStorage`] as they arrive, the pages are erased just before
+//! they are written. It works with any UART that implements `embedded_io::Read` and `Write`.
+//!
+//! A read error is handled as a timeout, so the read of the RX should return an error when no data
+//! is received for about 1 second.
+//!
+//! ```ignore
+//! let mut receiver = YmodemReceiver::new(uart_rx, uart_tx);
+//! let mut writer = flash.writer(SectorSize::Sz1K);
+//! if let Some(file) = receiver.receive_ymodem(&mut writer, 0x1_0000, 0x1_0000)? {
+//!     let len = file.size;
+//! }
+//! ```
+
+use super::{crc16::crc16_update, flash::FlashStorage};
+use embedded_io::{Read, Write};
+
+const SOH: u8 = 0x01;
+const STX: u8 = 0x02;
+const EOT: u8 = 0x04;
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+const CAN: u8 = 0x18;
+/// Request for the CRC mode
+const CRC_REQUEST: u8 = b'C';
+
+const PACKET_SIZE: usize = 128;
+const PACKET_1K_SIZE: usize = 1024;
+/// Block number, its complement and CRC
+const PACKET_OVERHEAD: usize = 4;
+pub const MAX_FILE_NAME_LEN: usize = 64;
+
+pub struct YmodemReceiver<R, W> {
+    rx: R,
+    tx: W,
+    max_errors: usize,
+    buf: [u8; PACKET_1K_SIZE + PACKET_OVERHEAD],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YmodemError<E> {
+    Flash(E),
+    /// The sender cancelled the transfer.
+    Cancelled,
+    /// Too many errors or timeouts in a row.
+    TooManyErrors,
+    /// The file doesn't fit in the given space.
+    TooLarge,
+    /// The block number is neither the expected one nor a repeated one.
+    OutOfSync,
+    /// Writing to the UART failed.
+    Write,
+}
+
+/// File received by YMODEM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileInfo {
+    /// Received bytes, without the padding of the last packet.
+    pub size: u32,
+    name: [u8; MAX_FILE_NAME_LEN],
+    name_len: usize,
+}
+
+impl FileInfo {
+    /// The name is truncated to [`MAX_FILE_NAME_LEN`] bytes.
+    pub fn name(&self) -> &[u8] {
+        &self.name[..self.name_len]
+    }
+}
+
+enum Packet {
+    Data { block: u8, len: usize },
+    Eot,
+    Cancel,
+    Error,
+}
+
+impl<R, W> YmodemReceiver<R, W>
+where
+    R: Read,
+    W: Write,
+{
+    pub fn new(rx: R, tx: W) -> Self {
+        Self {
+            rx,
+            tx,
+            max_errors: 10,
+            buf: [0; PACKET_1K_SIZE + PACKET_OVERHEAD],
+        }
+    }
+
+    /// Errors or timeouts in a row before the transfer is aborted, 10 by default.
+    pub fn set_max_errors(&mut self, max_errors: usize) {
+        self.max_errors = max_errors;
+    }
+
+    pub fn release(self) -> (R, W) {
+        (self.rx, self.tx)
+    }
+
+    /// Receive a file with XMODEM-CRC or XMODEM-1K to `start_offset`, which must be aligned to a
+    /// page. Returns the received bytes, including the padding of the last packet.
+    pub fn receive_xmodem<F: FlashStorage>(
+        &mut self,
+        flash: &mut F,
+        start_offset: u32,
+        max_len: u32,
+    ) -> Result<u32, YmodemError<F::Error>> {
+        self.receive_data(flash, start_offset, max_len, None, false)
+    }
+
+    /// Receive a file with YMODEM to `start_offset`, which must be aligned to a page.
+    /// Returns `None` if the sender has no file. Only the first file of a batch is received, the
+    /// rest are cancelled.
+    pub fn receive_ymodem<F: FlashStorage>(
+        &mut self,
+        flash: &mut F,
+        start_offset: u32,
+        max_len: u32,
+    ) -> Result<Option<FileInfo>, YmodemError<F::Error>> {
+        let Some((mut file, size)) = self.receive_header()? else {
+            return Ok(None);
+        };
+        if size.is_some_and(|s| s > max_len) {
+            self.cancel();
+            return Err(YmodemError::TooLarge);
+        }
+        file.size = self.receive_data(flash, start_offset, max_len, size, true)?;
+
+        // End of batch
+        if self.receive_header()?.is_some() {
+            self.cancel();
+        }
+        Ok(Some(file))
+    }
+
+    /// Receive the block 0 of YMODEM. Returns the file and its size, or `None` for an empty
+    /// header.
+    fn receive_header<E>(&mut self) -> Result<Option<(FileInfo, Option<u32>)>, YmodemError<E>> {
+        let mut errors = 0;
+        self.send(CRC_REQUEST)?;
+        loop {
+            match self.read_packet() {
+                Packet::Data { block: 0, len } => {
+                    self.send(ACK)?;
+                    return Ok(parse_header(&self.buf[2..2 + len]));
+                }
+                Packet::Cancel => return Err(YmodemError::Cancelled),
+                _ => {
+                    errors += 1;
+                    if errors > self.max_errors {
+                        self.cancel();
+                        return Err(YmodemError::TooManyErrors);
+                    }
+                    self.purge();
+                    self.send(CRC_REQUEST)?;
+                }
+            }
+        }
+    }
+
+    /// Receive the data blocks until the end of transmission. Only `size` bytes are written if
+    /// it's known.
+    fn receive_data<F: FlashStorage>(
+        &mut self,
+        flash: &mut F,
+        start_offset: u32,
+        max_len: u32,
+        size: Option<u32>,
+        is_ymodem: bool,
+    ) -> Result<u32, YmodemError<F::Error>> {
+        let page_size = flash.page_size() as u32;
+        assert!(start_offset.is_multiple_of(page_size));
+
+        let mut expected: u8 = 1;
+        let mut received = 0;
+        let mut erased_end = start_offset;
+        let mut errors = 0;
+        let mut eot_nak_sent = false;
+        self.send(CRC_REQUEST)?;
+        loop {
+            match self.read_packet() {
+                Packet::Data { block, len } if block == expected => {
+                    let len = match size {
+                        Some(size) => ((size - received) as usize).min(len),
+                        None => len,
+                    };
+                    let padded_len = (len as u32 + 1) & !1;
+                    if received + padded_len > max_len {
+                        self.cancel();
+                        return Err(YmodemError::TooLarge);
+                    }
+
+                    let offset = start_offset + received;
+                    while erased_end < offset + padded_len {
+                        flash.erase_page(erased_end).map_err(YmodemError::Flash)?;
+                        erased_end += page_size;
+                    }
+                    if len > 0 {
+                        flash
+                            .write(offset, &self.buf[2..2 + padded_len as usize])
+                            .map_err(YmodemError::Flash)?;
+                    }
+
+                    received += len as u32;
+                    expected = expected.wrapping_add(1);
+                    errors = 0;
+                    self.send(ACK)?;
+                }
+                // The ACK was lost
+                Packet::Data { block, .. } if block == expected.wrapping_sub(1) => {
+                    self.send(ACK)?;
+                }
+                Packet::Data { .. } => {
+                    self.cancel();
+                    return Err(YmodemError::OutOfSync);
+                }
+                // YMODEM confirms the EOT by a NAK first.
+                Packet::Eot if is_ymodem && !eot_nak_sent => {
+                    eot_nak_sent = true;
+                    self.send(NAK)?;
+                }
+                Packet::Eot => {
+                    self.send(ACK)?;
+                    return Ok(received);
+                }
+                Packet::Cancel => return Err(YmodemError::Cancelled),
+                Packet::Error => {
+                    errors += 1;
+                    if errors > self.max_errors {
+                        self.cancel();
+                        return Err(YmodemError::TooManyErrors);
+                    }
+                    self.purge();
+                    // Keep asking for the CRC mode until the first packet arrives.
+                    if received == 0 && expected == 1 {
+                        self.send(CRC_REQUEST)?;
+                    } else {
+                        self.send(NAK)?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read a packet into the buffer: block number, its complement, data and CRC.
+    fn read_packet(&mut self) -> Packet {
+        let mut head = [0];
+        if self.rx.read_exact(&mut head).is_err() {
+            return Packet::Error;
+        }
+        let len = match head[0] {
+            SOH => PACKET_SIZE,
+            STX => PACKET_1K_SIZE,
+            EOT => return Packet::Eot,
+            CAN => {
+                return match self.rx.read_exact(&mut head) {
+                    Ok(()) if head[0] == CAN => Packet::Cancel,
+                    _ => Packet::Error,
+                };
+            }
+            _ => return Packet::Error,
+        };
+
+        let packet = &mut self.buf[..len + PACKET_OVERHEAD];
+        if self.rx.read_exact(packet).is_err() {
+            return Packet::Error;
+        }
+        let block = packet[0];
+        let crc = u16::from_be_bytes([packet[len + 2], packet[len + 3]]);
+        if block != !packet[1] || crc != crc16_update(0, &packet[2..len + 2]) {
+            return Packet::Error;
+        }
+        Packet::Data { block, len }
+    }
+
+    /// Drop the incoming data until the line is idle.
+    fn purge(&mut self) {
+        let mut buf = [0; 16];
+        while matches!(self.rx.read(&mut buf), Ok(n) if n > 0) {}
+    }
+
+    fn cancel(&mut self) {
+        self.tx.write_all(&[CAN, CAN]).ok();
+        self.tx.flush().ok();
+    }
+
+    fn send<E>(&mut self, byte: u8) -> Result<(), YmodemError<E>> {
+        self.tx.write_all(&[byte]).map_err(|_| YmodemError::Write)?;
+        self.tx.flush().map_err(|_| YmodemError::Write)
+    }
+}
+
+// Utilities ------------------------------------------------------------------
+
+/// Block 0: file name, NUL, then the size in decimal followed by other optional fields.
+fn parse_header(data: &[u8]) -> Option<(FileInfo, Option<u32>)> {
+    if data[0] == 0 {
+        return None;
+    }
+    let name_end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    let mut file = FileInfo {
+        size: 0,
+        name: [0; MAX_FILE_NAME_LEN],
+        name_len: name_end.min(MAX_FILE_NAME_LEN),
+    };
+    file.name[..file.name_len].copy_from_slice(&data[..file.name_len]);
+
+    let mut size = None;
+    for &b in data[name_end..].iter().skip(1) {
+        if !b.is_ascii_digit() {
+            break;
+        }
+        let digit = (b - b'0') as u32;
+        size = Some(
+            size.unwrap_or(0u32)
+                .saturating_mul(10)
+                .saturating_add(digit),
+        );
+    }
+    Some((file, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::flash::RamFlash;
+    use embedded_io::{ErrorKind, ErrorType};
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    const PAGE_SIZE: usize = 256;
+    const PAGES: usize = 16;
+
+    /// One direction of an in-memory serial line
+    #[derive(Clone, Default)]
+    struct Pipe(Arc<Mutex<VecDeque<u8>>>);
+
+    impl ErrorType for Pipe {
+        type Error = ErrorKind;
+    }
+
+    impl Read for Pipe {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let deadline = Instant::now() + Duration::from_millis(100);
+            loop {
+                let mut queue = self.0.lock().unwrap();
+                if !queue.is_empty() {
+                    let n = buf.len().min(queue.len());
+                    for b in &mut buf[..n] {
+                        *b = queue.pop_front().unwrap();
+                    }
+                    return Ok(n);
+                }
+                drop(queue);
+                if Instant::now() > deadline {
+                    return Err(ErrorKind::TimedOut);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    impl Write for Pipe {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.lock().unwrap().extend(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    struct Sender {
+        rx: Pipe,
+        tx: Pipe,
+    }
+
+    impl Sender {
+        fn wait_for(&mut self, byte: u8) {
+            let mut b = [0];
+            while self.rx.read_exact(&mut b).is_err() || b[0] != byte {}
+        }
+
+        fn reply(&mut self) -> u8 {
+            let mut b = [0];
+            loop {
+                if self.rx.read_exact(&mut b).is_ok() && b[0] != CRC_REQUEST {
+                    return b[0];
+                }
+            }
+        }
+
+        /// Send until it's acknowledged, the CRC of the first try is wrong if `corrupt`.
+        fn send_packet(&mut self, block: u8, data: &[u8], size: usize, corrupt: bool) {
+            let mut packet = vec![if size == PACKET_SIZE { SOH } else { STX }, block, !block];
+            let mut payload = data.to_vec();
+            payload.resize(size, 0x1A);
+            let crc = crc16_update(0, &payload);
+            packet.extend(&payload);
+            packet.extend(crc.to_be_bytes());
+
+            let mut first = corrupt;
+            loop {
+                let mut p = packet.clone();
+                if first {
+                    *p.last_mut().unwrap() ^= 1;
+                    first = false;
+                }
+                self.tx.write_all(&p).unwrap();
+                if self.reply() == ACK {
+                    return;
+                }
+            }
+        }
+
+        fn send_eot(&mut self) -> u8 {
+            self.tx.write_all(&[EOT]).unwrap();
+            self.reply()
+        }
+    }
+
+    fn connect() -> (YmodemReceiver<Pipe, Pipe>, Sender) {
+        let a = Pipe::default();
+        let b = Pipe::default();
+        let sender = Sender {
+            rx: a.clone(),
+            tx: b.clone(),
+        };
+        (YmodemReceiver::new(b, a), sender)
+    }
+
+    fn image(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+    }
+
+    #[test]
+    fn xmodem() {
+        let (mut receiver, mut sender) = connect();
+        let data = image(1000);
+        let sent = data.clone();
+        let t = thread::spawn(move || {
+            sender.wait_for(CRC_REQUEST);
+            for (i, chunk) in sent.chunks(PACKET_SIZE).enumerate() {
+                sender.send_packet(i as u8 + 1, chunk, PACKET_SIZE, i == 2);
+            }
+            assert_eq!(sender.send_eot(), ACK);
+        });
+
+        let mut flash = RamFlash::new(PAGE_SIZE, PAGES);
+        let len = receiver
+            .receive_xmodem(&mut flash, PAGE_SIZE as u32, 2048)
+            .unwrap();
+        t.join().unwrap();
+        assert_eq!(len, 1024);
+        let bytes = &flash.as_bytes()[PAGE_SIZE..];
+        assert_eq!(&bytes[..1000], &data[..]);
+        assert!(bytes[1000..1024].iter().all(|&b| b == 0x1A));
+    }
+
+    #[test]
+    fn ymodem() {
+        let (mut receiver, mut sender) = connect();
+        let data = image(3000);
+        let sent = data.clone();
+        let t = thread::spawn(move || {
+            sender.wait_for(CRC_REQUEST);
+            sender.send_packet(0, b"fw.bin\x003000 0", PACKET_SIZE, false);
+            sender.wait_for(CRC_REQUEST);
+            for (i, chunk) in sent.chunks(PACKET_1K_SIZE).enumerate() {
+                sender.send_packet(i as u8 + 1, chunk, PACKET_1K_SIZE, i == 1);
+            }
+            assert_eq!(sender.send_eot(), NAK);
+            assert_eq!(sender.send_eot(), ACK);
+            sender.wait_for(CRC_REQUEST);
+            sender.send_packet(0, &[], PACKET_SIZE, false);
+        });
+
+        let mut flash = RamFlash::new(PAGE_SIZE, PAGES);
+        let file = receiver
+            .receive_ymodem(&mut flash, 0, (PAGE_SIZE * PAGES) as u32)
+            .unwrap()
+            .unwrap();
+        t.join().unwrap();
+        assert_eq!(file.name(), b"fw.bin");
+        assert_eq!(file.size, 3000);
+        assert_eq!(&flash.as_bytes()[..3000], &data[..]);
+        // Not written after the size
+        assert!(flash.as_bytes()[3000..].iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn too_large() {
+        let (mut receiver, mut sender) = connect();
+        let t = thread::spawn(move || {
+            sender.wait_for(CRC_REQUEST);
+            sender.tx.write_all(&[SOH]).unwrap();
+            let mut packet = vec![0, 0xFF];
+            let mut payload = b"big.bin\x005000".to_vec();
+            payload.resize(PACKET_SIZE, 0);
+            packet.extend(crc16_update(0, &payload).to_be_bytes());
+            packet.splice(2..2, payload);
+            sender.tx.write_all(&packet).unwrap();
+            assert_eq!(sender.reply(), ACK);
+            assert_eq!(sender.reply(), CAN);
+        });
+
+        let mut flash = RamFlash::new(PAGE_SIZE, PAGES);
+        let rst = receiver.receive_ymodem(&mut flash, 0, 4096);
+        t.join().unwrap();
+        assert_eq!(rst, Err(YmodemError::TooLarge));
+    }
+}
@@ -0,0 +1,210 @@
+//! # CRC calculation unit
+//!
+//! The hardware computes CRC-32 with the polynomial `0x04C11DB7`, initialized to `0xFFFFFFFF`,
+//! taking 32-bit words MSB first, without any reflection or final XOR.
+//!
+//! In [CrcMode::Standard], the input and the result are bit-reversed and the result is inverted,
+//! so that byte streams give the same result as the common CRC-32 (zlib, Ethernet, PNG).
+//!
+//! ```rust
+//! use core::hash::Hasher;
+//! let mut crc = dp.CRC.constrain(&mut mcu);
+//! crc.set_mode(CrcMode::Standard);
+//! crc.write(b"123456789");
+//! assert_eq!(crc.finish(), 0xCBF43926);
+//! ```
+
+use crate::{
+    Mcu,
+    common::dma::{DmaChannel, DmaError, DmaEvent},
+    pac::CRC,
+};
+use core::hash::Hasher;
+
+const POLY: u32 = 0x04C1_1DB7;
+const POLY_REFLECTED: u32 = 0xEDB8_8320;
+/// Maximum transfer length of a DMA channel
+const DMA_MAX_LEN: usize = u16::MAX as usize;
+
+pub trait CrcInit {
+    fn constrain(self, mcu: &mut Mcu) -> Crc;
+}
+
+impl CrcInit for CRC {
+    fn constrain(self, mcu: &mut Mcu) -> Crc {
+        mcu.rcc.enable(&self);
+        let mut crc = Crc {
+            crc: self,
+            mode: CrcMode::Stm32,
+            pending: [0; 4],
+            pending_len: 0,
+        };
+        crc.reset();
+        crc
+    }
+}
+
+pub struct Crc {
+    crc: CRC,
+    mode: CrcMode,
+    /// Bytes not yet packed into a word
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl Crc {
+    /// Set the mode used by the byte API, it also resets the calculation.
+    pub fn set_mode(&mut self, mode: CrcMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// Start a new calculation.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.crc.cr().write(|w| w.reset().reset());
+        self.pending_len = 0;
+    }
+
+    /// Feed a word to the hardware as it is, regardless of the mode.
+    #[inline]
+    pub fn feed_word(&mut self, word: u32) {
+        self.crc.dr().write(|w| w.dr().set(word));
+    }
+
+    /// Feed words to the hardware as they are, regardless of the mode.
+    pub fn feed_words(&mut self, words: &[u32]) {
+        for &word in words {
+            self.feed_word(word);
+        }
+    }
+
+    /// Feed words to the hardware as they are by a DMA channel, regardless of the mode.
+    /// It blocks until all the words are transferred.
+    pub fn feed_words_dma<CH: DmaChannel>(
+        &mut self,
+        ch: &mut CH,
+        words: &[u32],
+    ) -> Result<(), DmaError> {
+        let dr = self.crc.dr().as_ptr() as usize;
+        for chunk in words.chunks(DMA_MAX_LEN) {
+            ch.stop();
+            ch.set_memory_to_memory::<u32>(chunk.as_ptr() as usize, true, dr, false, chunk.len());
+            ch.start();
+            while ch.in_progress() {}
+            if ch.is_interrupted(DmaEvent::TransferError) {
+                ch.stop();
+                return Err(DmaError::TransferError);
+            }
+        }
+        ch.stop();
+        Ok(())
+    }
+
+    /// Raw value of the hardware register
+    #[inline]
+    pub fn get_value(&self) -> u32 {
+        self.crc.dr().read().dr().bits()
+    }
+
+    /// The independent data register, a general purpose byte not affected by the calculation.
+    pub fn read_idr(&self) -> u8 {
+        self.crc.idr().read().idr().bits()
+    }
+
+    pub fn write_idr(&mut self, value: u8) {
+        self.crc.idr().write(|w| w.idr().set(value));
+    }
+
+    fn feed_bytes_word(&mut self, bytes: [u8; 4]) {
+        let word = u32::from_le_bytes(bytes);
+        match self.mode {
+            CrcMode::Stm32 => self.feed_word(word),
+            CrcMode::Standard => self.feed_word(word.reverse_bits()),
+        }
+    }
+}
+
+/// Bytes are packed into little-endian words, the same as reading them from memory as words.
+/// The remaining bytes that don't fill a word are calculated in software by
+/// [finish](Hasher::finish).
+///
+/// Don't mix it with the word API without a [reset](Crc::reset).
+impl Hasher for Crc {
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.pending_len > 0 {
+            let n = (4 - self.pending_len).min(bytes.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&bytes[..n]);
+            self.pending_len += n;
+            bytes = &bytes[n..];
+            if self.pending_len < 4 {
+                return;
+            }
+            self.feed_bytes_word(self.pending);
+            self.pending_len = 0;
+        }
+
+        let mut chunks = bytes.chunks_exact(4);
+        for chunk in &mut chunks {
+            self.feed_bytes_word([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let rest = chunks.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let value = self.get_value();
+        let pending = &self.pending[..self.pending_len];
+        let crc = match self.mode {
+            CrcMode::Stm32 => pending.iter().fold(value, |c, &b| crc32_update_msb(c, b)),
+            CrcMode::Standard => {
+                let c = pending
+                    .iter()
+                    .fold(value.reverse_bits(), |c, &b| crc32_update_lsb(c, b));
+                !c
+            }
+        };
+        crc as u64
+    }
+}
+
+// Enumerate ------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrcMode {
+    /// The hardware result. Remaining bytes are calculated MSB first.
+    Stm32,
+    /// Compatible with the common CRC-32.
+    Standard,
+}
+
+// Utilities ------------------------------------------------------------------
+
+const fn crc32_update_msb(mut crc: u32, byte: u8) -> u32 {
+    crc ^= (byte as u32) << 24;
+    let mut i = 0;
+    while i < 8 {
+        crc = if crc & 0x8000_0000 != 0 {
+            (crc << 1) ^ POLY
+        } else {
+            crc << 1
+        };
+        i += 1;
+    }
+    crc
+}
+
+const fn crc32_update_lsb(mut crc: u32, byte: u8) -> u32 {
+    crc ^= byte as u32;
+    let mut i = 0;
+    while i < 8 {
+        crc = if crc & 1 != 0 {
+            (crc >> 1) ^ POLY_REFLECTED
+        } else {
+            crc >> 1
+        };
+        i += 1;
+    }
+    crc
+}
@@ -133,21 +133,7 @@ where
                 .bit(circular)
                 .dir()
                 .bit(mem_to_periph);
-
-            match core::mem::size_of::<T>() {
-                2 => {
-                    w.msize().bits16();
-                    w.psize().bits16()
-                }
-                4 => {
-                    w.msize().bits32();
-                    w.psize().bits32()
-                }
-                _ => {
-                    w.msize().bits8();
-                    w.psize().bits8()
-                }
-            }
+            set_data_size::<T>(w)
         });
     }
 
@@ -167,8 +153,35 @@ where
     }
 
     #[inline]
-    fn set_memory_to_memory<T: Sized>(&mut self, _src_addr: usize, _dst_addr: usize, _len: usize) {
-        todo!()
+    fn set_memory_to_memory<T: Sized>(
+        &mut self,
+        src_addr: usize,
+        src_increase: bool,
+        dst_addr: usize,
+        dst_increase: bool,
+        len: usize,
+    ) {
+        // Read from MAR and write to PAR
+        self.ch()
+            .par()
+            .write(|w| unsafe { w.pa().bits(dst_addr as u32) });
+        self.ch()
+            .mar()
+            .write(|w| unsafe { w.ma().bits(src_addr as u32) });
+        self.ch().cr().modify(|_, w| {
+            w.mem2mem()
+                .set_bit()
+                .pinc()
+                .bit(dst_increase)
+                .minc()
+                .bit(src_increase)
+                .circ()
+                .clear_bit()
+                .dir()
+                .set_bit();
+            set_data_size::<T>(w)
+        });
+        self.set_transfer_length(len);
     }
 
     #[inline]
@@ -178,7 +191,8 @@ where
 
     #[inline]
     fn in_progress(&self) -> bool {
-        self.get_left_len() != 0 && self.dma.isr().read().tcif(C).bit_is_clear()
+        let isr = self.dma.isr().read();
+        self.get_left_len() != 0 && isr.tcif(C).bit_is_clear() && isr.teif(C).bit_is_clear()
     }
 
     #[inline]
@@ -186,6 +200,7 @@ where
         match event {
             DmaEvent::HalfTransfer => self.ch().cr().modify(|_, w| w.htie().bit(enable)),
             DmaEvent::TransferComplete => self.ch().cr().modify(|_, w| w.tcie().bit(enable)),
+            DmaEvent::TransferError => self.ch().cr().modify(|_, w| w.teie().bit(enable)),
         };
     }
 
@@ -208,12 +223,44 @@ where
                     false
                 }
             }
+            DmaEvent::TransferError => {
+                if self.dma.isr().read().teif(C).bit_is_set() {
+                    self.dma.ifcr().write(|w| w.cteif(C).set_bit());
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn set_data_size<T: Sized>(w: &mut pac::dma1::ch::cr::W) -> &mut pac::dma1::ch::cr::W {
+    match core::mem::size_of::<T>() {
+        2 => {
+            w.msize().bits16();
+            w.psize().bits16()
+        }
+        4 => {
+            w.msize().bits32();
+            w.psize().bits32()
+        }
+        _ => {
+            w.msize().bits8();
+            w.psize().bits8()
         }
     }
 }
 
 pub trait DmaBindTx<U>: DmaChannel {}
 pub trait DmaBindRx<U>: DmaChannel {}
+/// Timer requests, the update event and the capture/compare channels
+pub trait DmaBindTimUp<T>: DmaChannel {}
+pub trait DmaBindTimCh1<T>: DmaChannel {}
+pub trait DmaBindTimCh2<T>: DmaChannel {}
+pub trait DmaBindTimCh3<T>: DmaChannel {}
+pub trait DmaBindTimCh4<T>: DmaChannel {}
 
 // table
 // Do NOT manually modify the code.
@@ -239,3 +286,56 @@ impl DmaBindTx<pac::I2C2> for dma1::C4 {}
 impl DmaBindRx<pac::I2C2> for dma1::C5 {}
 impl DmaBindTx<pac::I2C1> for dma1::C6 {}
 impl DmaBindRx<pac::I2C1> for dma1::C7 {}
+
+impl DmaBindTimCh3<pac::TIM2> for dma1::C1 {}
+#[cfg(feature = "medium")]
+impl DmaBindTimCh1<pac::TIM4> for dma1::C1 {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl DmaBindTimCh1<pac::TIM1> for dma1::C2 {}
+impl DmaBindTimUp<pac::TIM2> for dma1::C2 {}
+impl DmaBindTimCh3<pac::TIM3> for dma1::C2 {}
+impl DmaBindTimCh4<pac::TIM3> for dma1::C3 {}
+impl DmaBindTimUp<pac::TIM3> for dma1::C3 {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl DmaBindTimCh4<pac::TIM1> for dma1::C4 {}
+#[cfg(feature = "medium")]
+impl DmaBindTimCh2<pac::TIM4> for dma1::C4 {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl DmaBindTimUp<pac::TIM1> for dma1::C5 {}
+impl DmaBindTimCh1<pac::TIM2> for dma1::C5 {}
+#[cfg(feature = "medium")]
+impl DmaBindTimCh3<pac::TIM4> for dma1::C5 {}
+#[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
+impl DmaBindTimCh3<pac::TIM1> for dma1::C6 {}
+impl DmaBindTimCh1<pac::TIM3> for dma1::C6 {}
+impl DmaBindTimCh2<pac::TIM2> for dma1::C7 {}
+impl DmaBindTimCh4<pac::TIM2> for dma1::C7 {}
+#[cfg(feature = "medium")]
+impl DmaBindTimUp<pac::TIM4> for dma1::C7 {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl DmaBindTimCh4<pac::TIM5> for dma2::C1 {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl DmaBindTimCh3<pac::TIM8> for dma2::C1 {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl DmaBindTimUp<pac::TIM8> for dma2::C1 {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl DmaBindTimCh3<pac::TIM5> for dma2::C2 {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl DmaBindTimUp<pac::TIM5> for dma2::C2 {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl DmaBindTimCh4<pac::TIM8> for dma2::C2 {}
+#[cfg(any(feature = "stm32f100", feature = "high", feature = "connectivity"))]
+impl DmaBindTimUp<pac::TIM6> for dma2::C3 {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl DmaBindTimCh1<pac::TIM8> for dma2::C3 {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl DmaBindTimCh2<pac::TIM5> for dma2::C4 {}
+#[cfg(any(
+    all(feature = "high", any(feature = "stm32f101", feature = "stm32f103")),
+    any(feature = "stm32f100", feature = "connectivity")
+))]
+impl DmaBindTimUp<pac::TIM7> for dma2::C4 {}
+#[cfg(any(feature = "high", feature = "connectivity"))]
+impl DmaBindTimCh1<pac::TIM5> for dma2::C5 {}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl DmaBindTimCh2<pac::TIM8> for dma2::C5 {}
@@ -1,16 +1,25 @@
 //! Flash memory
 
-use crate::pac::{flash, FLASH};
+use crate::{
+    nvic_scb::Scb,
+    pac::{flash, FLASH},
+    signature,
+};
+
+pub use crate::common::flash::*;
 
 pub const FLASH_START: u32 = 0x0800_0000;
 pub const FLASH_END: u32 = 0x080F_FFFF;
 
-const _RDPRT_KEY: u16 = 0x00A5;
+const RDPRT_KEY: u8 = 0xA5;
 const KEY1: u32 = 0x45670123;
 const KEY2: u32 = 0xCDEF89AB;
 
 pub const SZ_1K: u16 = 1024;
 
+/// Address of the option bytes, each byte takes a half-word together with its complement.
+const OPTION_BYTES_ADDR: u32 = 0x1FFF_F800;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
@@ -55,6 +64,28 @@ impl FlashSize {
     const fn kbytes(self) -> u32 {
         SZ_1K as u32 * self as u32
     }
+
+    /// The largest size not exceeding `kbytes`, `None` if it's out of the range of the F1
+    /// devices, from 16 to 1024.
+    pub const fn from_kbytes(kbytes: u16) -> Option<Self> {
+        Some(match kbytes {
+            16..32 => Self::Sz16K,
+            32..64 => Self::Sz32K,
+            64..128 => Self::Sz64K,
+            128..256 => Self::Sz128K,
+            256..384 => Self::Sz256K,
+            384..512 => Self::Sz384K,
+            512..768 => Self::Sz512K,
+            768..1024 => Self::Sz768K,
+            1024 => Self::Sz1M,
+            _ => return None,
+        })
+    }
+
+    /// Read the size from the flash size register, `None` if the register is not programmed.
+    pub fn from_device() -> Option<Self> {
+        Self::from_kbytes(signature::flash_size_kbytes())
+    }
 }
 
 pub struct FlashWriter<'a> {
@@ -300,6 +331,27 @@ impl FlashWriter<'_> {
     }
 }
 
+impl FlashStorage for FlashWriter<'_> {
+    type Error = Error;
+
+    fn page_size(&self) -> usize {
+        self.sector_sz.kbytes() as usize
+    }
+
+    fn read(&self, offset: u32, buf: &mut [u8]) -> Result<()> {
+        buf.copy_from_slice(FlashWriter::read(self, offset, buf.len())?);
+        Ok(())
+    }
+
+    fn erase_page(&mut self, offset: u32) -> Result<()> {
+        self.page_erase(offset)
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        FlashWriter::write(self, offset, data)
+    }
+}
+
 /// Extension trait to constrain the FLASH peripheral
 pub trait FlashExt {
     /// Constrains the FLASH peripheral to play nicely with the other abstractions
@@ -313,10 +365,10 @@ impl FlashExt for FLASH {
             ar: AR,
             cr: CR,
             keyr: KEYR,
-            _obr: OBR,
-            _optkeyr: OPTKEYR,
+            obr: OBR,
+            optkeyr: OPTKEYR,
             sr: SR,
-            _wrpr: WRPR,
+            wrpr: WRPR,
         }
     }
 }
@@ -336,19 +388,33 @@ pub struct Parts {
     pub(crate) keyr: KEYR,
 
     /// Opaque OBR register
-    pub(crate) _obr: OBR,
+    pub(crate) obr: OBR,
 
     /// Opaque OPTKEYR register
-    pub(crate) _optkeyr: OPTKEYR,
+    pub(crate) optkeyr: OPTKEYR,
 
     /// Opaque SR register
     pub(crate) sr: SR,
 
     /// Opaque WRPR register
-    pub(crate) _wrpr: WRPR,
+    pub(crate) wrpr: WRPR,
 }
 impl Parts {
-    pub fn writer(&mut self, sector_sz: SectorSize, flash_sz: FlashSize) -> FlashWriter<'_> {
+    /// The flash size is read from the device.
+    ///
+    /// Panics if the flash size register is not programmed, use
+    /// [writer_with_flash_size](Self::writer_with_flash_size) then.
+    pub fn writer(&mut self, sector_sz: SectorSize) -> FlashWriter<'_> {
+        let flash_sz = FlashSize::from_device().expect("invalid flash size register");
+        self.writer_with_flash_size(sector_sz, flash_sz)
+    }
+
+    /// Some devices have more flash than the flash size register reports.
+    pub fn writer_with_flash_size(
+        &mut self,
+        sector_sz: SectorSize,
+        flash_sz: FlashSize,
+    ) -> FlashWriter<'_> {
         FlashWriter {
             flash: self,
             sector_sz,
@@ -356,6 +422,134 @@ impl Parts {
             verify: true,
         }
     }
+
+    /// Read the option bytes loaded at the last reset.
+    pub fn read_option_bytes(&self) -> OptionBytes {
+        let obr = self.obr.obr().read();
+        let mut user = UserOption::empty();
+        user.set(UserOption::WdgSw, obr.wdg_sw().bit_is_set());
+        user.set(UserOption::NRstStop, obr.n_rst_stop().bit_is_set());
+        user.set(UserOption::NRstStdby, obr.n_rst_stdby().bit_is_set());
+        OptionBytes {
+            read_protection: obr.rdprt().bit_is_set(),
+            write_protection: !self.wrpr.wrpr().read().wrp().bits(),
+            user,
+            data: [obr.data0().bits(), obr.data1().bits()],
+        }
+    }
+
+    /// Erase and program all the option bytes. They are loaded at the next system reset, see
+    /// [reload_option_bytes](Self::reload_option_bytes).
+    ///
+    /// Note, removing the read protection erases the whole flash memory.
+    pub fn program_option_bytes(&mut self, ob: &OptionBytes) -> Result<()> {
+        self.unlock_option_bytes()?;
+        let ret = self.erase_program_option_bytes(ob);
+        self.lock_option_bytes();
+        ret
+    }
+
+    /// Reset the system to load the option bytes.
+    pub fn reload_option_bytes(&mut self, scb: &mut Scb) -> ! {
+        scb.system_reset()
+    }
+
+    fn erase_program_option_bytes(&mut self, ob: &OptionBytes) -> Result<()> {
+        self.cr.cr().modify(|_, w| w.opter().set_bit());
+        self.cr.cr().modify(|_, w| w.strt().set_bit());
+        // Wait for the BSY bit, see page_erase
+        cortex_m::asm::nop();
+        while self.sr.sr().read().bsy().bit_is_set() {}
+        self.cr.cr().modify(|_, w| w.opter().clear_bit());
+        if self.sr.sr().read().wrprterr().bit_is_set() {
+            self.sr.sr().modify(|_, w| w.wrprterr().bit(true));
+            return Err(Error::EraseError);
+        }
+
+        let rdp = if ob.read_protection { 0x00 } else { RDPRT_KEY };
+        // Reserved bits are kept at 1
+        let user = 0xF8 | ob.user.bits();
+        let wrp = (!ob.write_protection).to_le_bytes();
+        let bytes = [
+            rdp, user, ob.data[0], ob.data[1], wrp[0], wrp[1], wrp[2], wrp[3],
+        ];
+
+        self.cr.cr().modify(|_, w| w.optpg().set_bit());
+        let mut ret = Ok(());
+        for (i, byte) in bytes.into_iter().enumerate() {
+            let address = (OPTION_BYTES_ADDR + i as u32 * 2) as *mut u16;
+            // NOTE(unsafe) Write to the option bytes area, the complement is programmed by
+            // hardware
+            unsafe { core::ptr::write_volatile(address, byte as u16) };
+            while self.sr.sr().read().bsy().bit_is_set() {}
+
+            if self.sr.sr().read().pgerr().bit_is_set() {
+                self.sr.sr().modify(|_, w| w.pgerr().bit(true));
+                ret = Err(Error::ProgrammingError);
+                break;
+            } else if self.sr.sr().read().wrprterr().bit_is_set() {
+                self.sr.sr().modify(|_, w| w.wrprterr().bit(true));
+                ret = Err(Error::WriteError);
+                break;
+            }
+        }
+        self.cr.cr().modify(|_, w| w.optpg().clear_bit());
+        ret
+    }
+
+    fn unlock_option_bytes(&mut self) -> Result<()> {
+        while self.sr.sr().read().bsy().bit_is_set() {}
+
+        // NOTE(unsafe) The same as FlashWriter::unlock, the flash must be unlocked before the
+        // option bytes.
+        unsafe {
+            self.keyr.keyr().write(|w| w.key().bits(KEY1));
+            self.keyr.keyr().write(|w| w.key().bits(KEY2));
+            self.optkeyr.optkeyr().write(|w| w.optkey().bits(KEY1));
+            self.optkeyr.optkeyr().write(|w| w.optkey().bits(KEY2));
+        }
+
+        match self.cr.cr().read().optwre().bit_is_set() {
+            true => Ok(()),
+            false => Err(Error::UnlockError),
+        }
+    }
+
+    fn lock_option_bytes(&mut self) {
+        while self.sr.sr().read().bsy().bit_is_set() {}
+        self.cr
+            .cr()
+            .modify(|_, w| w.optwre().clear_bit().lock().set_bit());
+    }
+}
+
+/// Option bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionBytes {
+    /// Read protection of the flash memory
+    pub read_protection: bool,
+    /// Write protection, a set bit protects a group of pages.
+    ///
+    /// - Low and medium density devices: each bit protects 4 pages (4 KB).
+    /// - High density and connectivity line devices: bit 0 to 30 protect 2 pages each (4 KB),
+    ///   bit 31 protects the rest of the pages.
+    pub write_protection: u32,
+    pub user: UserOption,
+    /// User data bytes
+    pub data: [u8; 2],
+}
+
+bitflags::bitflags! {
+    /// User option bits
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct UserOption: u8 {
+        /// Software watchdog, otherwise IWDG is started by hardware
+        const WdgSw = 1 << 0;
+        /// No reset when entering Stop mode
+        const NRstStop = 1 << 1;
+        /// No reset when entering Standby mode
+        const NRstStdby = 1 << 2;
+    }
 }
 
 /// Opaque ACR register
@@ -412,8 +606,8 @@ pub struct OBR;
 
 #[allow(dead_code)]
 impl OBR {
-    pub(crate) fn obr(&mut self) -> &flash::OBR {
-        // NOTE(unsafe) this proxy grants exclusive access to this register
+    pub(crate) fn obr(&self) -> &flash::OBR {
+        // NOTE(unsafe) this register is read-only
         unsafe { (*FLASH::ptr()).obr() }
     }
 }
@@ -448,8 +642,8 @@ pub struct WRPR;
 
 #[allow(dead_code)]
 impl WRPR {
-    pub(crate) fn wrpr(&mut self) -> &flash::WRPR {
-        // NOTE(unsafe) this proxy grants exclusive access to this register
+    pub(crate) fn wrpr(&self) -> &flash::WRPR {
+        // NOTE(unsafe) this register is read-only
         unsafe { (*FLASH::ptr()).wrpr() }
     }
 }
@@ -209,6 +209,21 @@ pub enum Edge {
     RisingFalling,
 }
 
+/// Select the trigger edge of EXTI line `line`, it's shared by the pins and the internal lines.
+pub(crate) fn set_trigger_edge(exti: &EXTI, line: u8, edge: Edge) {
+    let bit = 1 << line;
+    let (rising, falling) = match edge {
+        Edge::Rising => (true, false),
+        Edge::Falling => (false, true),
+        Edge::RisingFalling => (true, true),
+    };
+    let set = |value: u32, set: bool| if set { value | bit } else { value & !bit };
+    exti.rtsr()
+        .modify(|r, w| unsafe { w.bits(set(r.bits(), rising)) });
+    exti.ftsr()
+        .modify(|r, w| unsafe { w.bits(set(r.bits(), falling)) });
+}
+
 mod sealed {
     /// Marker trait that show if `ExtiPin` can be implemented
     pub trait Interruptable {}
@@ -276,27 +291,7 @@ where
     /// Generate interrupt on rising edge, falling edge or both
     fn trigger_on_edge(&mut self, edge: Edge) {
         let exti = unsafe { EXTI::steal() };
-        let pin_number = self.pin_id();
-        match edge {
-            Edge::Rising => {
-                exti.rtsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() | (1 << pin_number)) });
-                exti.ftsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() & !(1 << pin_number)) });
-            }
-            Edge::Falling => {
-                exti.rtsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() & !(1 << pin_number)) });
-                exti.ftsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() | (1 << pin_number)) });
-            }
-            Edge::RisingFalling => {
-                exti.rtsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() | (1 << pin_number)) });
-                exti.ftsr()
-                    .modify(|r, w| unsafe { w.bits(r.bits() | (1 << pin_number)) });
-            }
-        }
+        set_trigger_edge(&exti, self.pin_id(), edge);
     }
 
     /// Enable external interrupts from this pin.
@@ -0,0 +1,205 @@
+//! # In-Application Programming
+//!
+//! Firmware update with two slots in the flash memory:
+//!
+//! - Active slot: the running application, it starts after the bootloader.
+//! - Update slot: the new image is written here by the application, then verified and marked
+//!   pending in a backup register.
+//!
+//! After a reset, the bootloader verifies the pending image again, copies it to the active slot
+//! and jumps to it. An interrupted copy is redone at the next reset, because the pending mark is
+//! only cleared after the copy is verified.
+//!
+//! The image is verified with the standard CRC-32 (the same as zlib), so it can be computed by the
+//! host tools.
+//!
+//! ```rust
+//! // Application
+//! let mut iap = Iap::new(flash.writer(SectorSize::Sz1K), LAYOUT);
+//! iap.erase_update_slot()?;
+//! iap.write_update(0, &chunk)?;
+//! // ...
+//! iap.mark_pending(image_len, image_crc, &mut crc, &mut bkp, BackupReg::Dr1)?;
+//! scb.system_reset();
+//!
+//! // Bootloader
+//! let mut iap = Iap::new(flash.writer(SectorSize::Sz1K), LAYOUT);
+//! iap.install_pending(&mut crc, &mut bkp, BackupReg::Dr1).ok();
+//! unsafe { iap::jump_to_application(&mut scb, LAYOUT.active_address()) };
+//! ```
+
+use crate::{
+    backup_domain::{BackupDomain, BackupReg},
+    crc::{Crc, CrcMode},
+    flash::{Error, FLASH_START, FlashWriter, Result},
+    nvic_scb::Scb,
+};
+use core::hash::Hasher;
+
+/// Value of the backup register when an update is pending
+const UPDATE_PENDING: u16 = 0xB007;
+/// Image length and CRC at the end of the update slot
+const TRAILER_SIZE: u32 = 8;
+const COPY_CHUNK_SIZE: usize = 256;
+
+/// Offsets from the start of the flash memory, aligned to pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotLayout {
+    pub active: u32,
+    pub update: u32,
+    /// Size of each slot
+    pub size: u32,
+}
+
+impl SlotLayout {
+    pub const fn active_address(&self) -> u32 {
+        FLASH_START + self.active
+    }
+
+    /// Maximum image size, the end of the update slot keeps the image length and CRC.
+    pub const fn max_image_size(&self) -> u32 {
+        self.size - TRAILER_SIZE
+    }
+}
+
+pub struct Iap<'a> {
+    writer: FlashWriter<'a>,
+    layout: SlotLayout,
+}
+
+impl<'a> Iap<'a> {
+    pub fn new(writer: FlashWriter<'a>, layout: SlotLayout) -> Self {
+        Self { writer, layout }
+    }
+
+    pub fn erase_update_slot(&mut self) -> Result<()> {
+        self.writer
+            .erase(self.layout.update, self.layout.size as usize)
+    }
+
+    /// Write a part of the image at `offset` from the start of the image.
+    /// The length must be a multiple of 2, except for the last part.
+    pub fn write_update(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        if offset + data.len() as u32 > self.layout.max_image_size() {
+            return Err(Error::LengthTooLong);
+        }
+        let address = self.layout.update + offset;
+        let even = data.len() & !1;
+        self.writer.write(address, &data[..even])?;
+        if even < data.len() {
+            self.writer
+                .write(address + even as u32, &[data[even], 0xFF])?;
+        }
+        Ok(())
+    }
+
+    /// Verify the image in the update slot, then mark it pending in `reg`.
+    ///
+    /// Marking the same image again is allowed. Another image needs
+    /// [`erase_update_slot`](Self::erase_update_slot) first, the trailer can't be rewritten, so
+    /// this returns `Error::WriteError`.
+    pub fn mark_pending(
+        &mut self,
+        len: u32,
+        crc_value: u32,
+        crc: &mut Crc,
+        bkp: &mut BackupDomain,
+        reg: BackupReg,
+    ) -> Result<()> {
+        if len > self.layout.max_image_size() {
+            return Err(Error::LengthTooLong);
+        }
+        if self.image_crc(self.layout.update, len, crc)? != crc_value {
+            return Err(Error::VerifyError);
+        }
+        let mut trailer = [0; TRAILER_SIZE as usize];
+        trailer[..4].copy_from_slice(&len.to_le_bytes());
+        trailer[4..].copy_from_slice(&crc_value.to_le_bytes());
+        let current = self
+            .writer
+            .read(self.trailer_offset(), TRAILER_SIZE as usize)?;
+        if current != trailer {
+            if current.iter().any(|&b| b != 0xFF) {
+                return Err(Error::WriteError);
+            }
+            self.writer.write(self.trailer_offset(), &trailer)?;
+        }
+        bkp.write(reg, UPDATE_PENDING);
+        Ok(())
+    }
+
+    pub fn is_pending(bkp: &BackupDomain, reg: BackupReg) -> bool {
+        bkp.read(reg) == UPDATE_PENDING
+    }
+
+    /// Called by the bootloader. Returns `true` if a pending image has been copied to the active
+    /// slot. An image that fails the verification is discarded.
+    pub fn install_pending(
+        &mut self,
+        crc: &mut Crc,
+        bkp: &mut BackupDomain,
+        reg: BackupReg,
+    ) -> Result<bool> {
+        if !Self::is_pending(bkp, reg) {
+            return Ok(false);
+        }
+
+        let trailer = self
+            .writer
+            .read(self.trailer_offset(), TRAILER_SIZE as usize)?;
+        let len = u32::from_le_bytes(trailer[..4].try_into().unwrap());
+        let crc_value = u32::from_le_bytes(trailer[4..].try_into().unwrap());
+        if len > self.layout.max_image_size()
+            || self.image_crc(self.layout.update, len, crc)? != crc_value
+        {
+            bkp.write(reg, 0);
+            return Err(Error::VerifyError);
+        }
+
+        self.writer
+            .erase(self.layout.active, self.layout.size as usize)?;
+        let mut buf = [0; COPY_CHUNK_SIZE];
+        let mut offset = 0;
+        while offset < len {
+            let n = ((len - offset) as usize).min(COPY_CHUNK_SIZE);
+            // Keep the length even, the padding byte is erased.
+            let n2 = (n + 1) & !1;
+            let src = self.writer.read(self.layout.update + offset, n2)?;
+            buf[..n2].copy_from_slice(src);
+            self.writer.write(self.layout.active + offset, &buf[..n2])?;
+            offset += n as u32;
+        }
+
+        if self.image_crc(self.layout.active, len, crc)? != crc_value {
+            return Err(Error::VerifyError);
+        }
+        bkp.write(reg, 0);
+        Ok(true)
+    }
+
+    pub fn release(self) -> FlashWriter<'a> {
+        self.writer
+    }
+
+    fn image_crc(&self, offset: u32, len: u32, crc: &mut Crc) -> Result<u32> {
+        crc.set_mode(CrcMode::Standard);
+        crc.write(self.writer.read(offset, len as usize)?);
+        Ok(crc.finish() as u32)
+    }
+
+    #[inline]
+    fn trailer_offset(&self) -> u32 {
+        self.layout.update + self.layout.max_image_size()
+    }
+}
+
+/// Jump to the application whose vector table is at `address`, a multiple of 512.
+///
+/// # Safety
+///
+/// The interrupts and the peripherals used by the bootloader should be disabled in the NVIC
+/// first, the application starts from its reset handler with the current state of the hardware.
+pub unsafe fn jump_to_application(scb: &mut Scb, address: u32) -> ! {
+    scb.set_vector_table(address);
+    unsafe { cortex_m::asm::bootload(address as *const u32) }
+}
@@ -7,16 +7,21 @@ cfg_if::cfg_if! {
         pub mod afio;
         pub mod backup_domain;
         pub mod bb;
+        pub mod crc;
         pub mod dma;
         pub mod flash;
         pub mod gpio;
+        pub mod iap;
         pub mod interrupt;
         pub mod nvic_scb;
         pub mod prelude;
+        pub mod pwr;
         pub mod rcc;
+        pub mod signature;
         pub mod time;
         pub mod timer;
         pub mod uart;
+        pub mod wwdg;
         pub mod mcu;
         pub use mcu::Mcu;
         pub use cortex_m;
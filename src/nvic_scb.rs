@@ -1,6 +1,8 @@
 use crate::pac::Interrupt;
+use crate::rcc::Rcc;
 use cortex_m::peripheral::NVIC;
 use cortex_m::peripheral::SCB;
+use cortex_m::peripheral::SYST;
 
 pub trait ScbInit {
     fn constrain(self) -> Scb;
@@ -29,7 +31,10 @@ impl NvicInit for NVIC {
 const SCB_AIRCR_VECTKEY_MASK: u32 = 0xFFFF << 16;
 const SCB_AIRCR_VECTKEY: u32 = 0x05FA << 16;
 const SCB_AIRCR_PRIGROUP_MASK: u32 = 0x7 << 8;
-// const SCB_AIRCR_SYSRESETREQ: u32 = 1 << 2;
+const SCB_AIRCR_SYSRESETREQ: u32 = 1 << 2;
+/// The vector table has 84 entries, so the offset is aligned to 512 bytes.
+const VTOR_ALIGN: u32 = 0x200;
+const SYSTEM_MEMORY_ADDR: u32 = 0x1FFF_F000;
 
 impl Scb {
     /// Call it as early as possible.
@@ -49,6 +54,50 @@ impl Scb {
     pub fn get_priority_grouping(&self) -> PriorityGrouping {
         self.scb.aircr.read().into()
     }
+
+    /// Relocate the vector table, for an application linked behind a bootloader.
+    /// The address must be a multiple of 512.
+    pub fn set_vector_table(&mut self, address: u32) {
+        assert!(address.is_multiple_of(VTOR_ALIGN));
+        unsafe { self.scb.vtor.write(address) };
+        cortex_m::asm::dsb();
+    }
+
+    pub fn get_vector_table(&self) -> u32 {
+        self.scb.vtor.read()
+    }
+
+    /// Request a system reset, the priority grouping is kept until the reset.
+    pub fn system_reset(&mut self) -> ! {
+        let prigroup = self.scb.aircr.read() & SCB_AIRCR_PRIGROUP_MASK;
+        cortex_m::asm::dsb();
+        unsafe {
+            self.scb
+                .aircr
+                .write(SCB_AIRCR_VECTKEY | prigroup | SCB_AIRCR_SYSRESETREQ)
+        };
+        cortex_m::asm::dsb();
+        loop {
+            cortex_m::asm::nop();
+        }
+    }
+
+    /// Jump to the ROM system bootloader.
+    ///
+    /// The clocks are switched back to the HSI, the SysTick and all the NVIC lines are disabled,
+    /// then the bootloader starts as after a reset.
+    ///
+    /// # Safety
+    ///
+    /// The peripherals used by the bootloader (USART1, and USART2/USART3/CAN/USB depending on the
+    /// device) must be disabled, and no interrupt may be masked by `PRIMASK`.
+    pub unsafe fn jump_to_system_bootloader(&mut self, nvic: &mut Nvic, rcc: &mut Rcc) -> ! {
+        unsafe { (*SYST::PTR).csr.write(0) };
+        nvic.disable_all();
+        rcc.deinit();
+        self.set_vector_table(SYSTEM_MEMORY_ADDR);
+        unsafe { cortex_m::asm::bootload(SYSTEM_MEMORY_ADDR as *const u32) }
+    }
 }
 
 impl Nvic {
@@ -71,6 +120,18 @@ impl Nvic {
             NVIC::mask(it);
         }
     }
+
+    /// Disable all the interrupts and clear the pending ones.
+    pub fn disable_all(&mut self) {
+        for (icer, icpr) in self.nvic.icer.iter().zip(self.nvic.icpr.iter()) {
+            unsafe {
+                icer.write(u32::MAX);
+                icpr.write(u32::MAX);
+            }
+        }
+        cortex_m::asm::dsb();
+        cortex_m::asm::isb();
+    }
 }
 
 pub enum PriorityGrouping {
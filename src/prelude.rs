@@ -2,6 +2,7 @@ pub use crate::afio::AfioInit as _stm32_hal_afio_AfioInit;
 pub use crate::afio::RFrom as _;
 pub use crate::afio::RInto as _;
 pub use crate::afio::Remap as _;
+pub use crate::crc::CrcInit as _;
 pub use crate::flash::FlashExt as _stm32_hal_flash_FlashExt;
 pub use crate::gpio::GpioExt as _stm32_hal_gpio_GpioExt;
 pub use crate::rcc::BkpExt as _;
@@ -11,16 +12,16 @@ pub use crate::time::U32Ext as _stm32_hal_time_U32Ext;
 pub use crate::timer::MonoTimerExt as _stm32f4xx_hal_timer_MonoTimerExt;
 pub use crate::timer::SysTimerInit as _stm32_hal_timer_SysCounterInit;
 pub use crate::timer::TimerInit as _stm32_hal_timer_TimerInit;
-// pub use crate::timer::pwm_input::PwmInputExt as _;
-// pub use crate::timer::pwm_input::QeiExt as _;
 pub use crate::dma::DmaInit as _;
 pub use crate::nvic_scb::NvicInit as _;
 pub use crate::nvic_scb::ScbInit as _;
+pub use crate::pwr::PwrInit as _;
 pub use crate::ringbuf::ConsumerExt;
 pub use crate::ringbuf::ProducerExt;
 pub use crate::ringbuf::ReadChunkExt;
 pub use crate::ringbuf::WriteChunkExt;
 pub use crate::uart::UartInit as _;
+pub use crate::wwdg::WwdgInit as _;
 pub use cortex_m;
 pub use cortex_m_rt;
 pub use fugit::ExtU32 as _fugit_ExtU32;
@@ -0,0 +1,290 @@
+//! # Power Control
+//!
+//! Low-power modes, from the lowest to the highest power saving:
+//!
+//! - **Sleep**: only the CPU clock is stopped. Any interrupt or event wakes it up.
+//! - **Stop**: all the clocks in the 1.8 V domain are stopped, and the PLL, HSI and HSE are
+//!   switched off. SRAM and registers are kept. Any EXTI line (e.g. a pin, or the RTC alarm on
+//!   line 17) wakes it up.
+//! - **Standby**: the 1.8 V domain is powered off, only the backup domain is kept. It's woken up by
+//!   the WKUP pin (PA0), the RTC alarm, NRST or IWDG, and then the MCU starts over from reset.
+//!
+//! ```rust
+//! let mut pwr = dp.PWR.constrain(&mut mcu);
+//! pwr.stop(StopMode::LowPowerRegulator, SleepEntry::Wfi, &mut mcu);
+//! // The clocks have been restored here.
+//! ```
+//!
+//! ## Programmable voltage detector
+//!
+//! The PVD compares VDD with a threshold, and can raise an interrupt through EXTI line 16 when
+//! the power supply is going down, leaving a short time to save some state.
+//!
+//! ```rust
+//! let mut pvd = pwr.get_pvd_interrupt_handler();
+//! all_it::PVD_CB.set(&mut mcu, move || {
+//!     if pvd.is_interrupted() && pvd.is_below_threshold() {
+//!         bkp.write_data_register_low(0, 0xDEAD);
+//!     }
+//! });
+//! pwr.enable_pvd(PvdLevel::V2_9);
+//! pwr.listen_pvd(Edge::Rising, &mut mcu);
+//! ```
+
+use crate::{
+    Mcu, Steal,
+    gpio::{self, Edge, set_trigger_edge},
+    pac::{EXTI, PWR},
+};
+use core::ops::{Deref, DerefMut};
+
+/// EXTI line connected to the PVD output
+const EXTI_LINE_PVD: u8 = 16;
+/// EXTI line connected to the RTC alarm
+const EXTI_LINE_RTC_ALARM: u8 = 17;
+
+pub trait PwrInit {
+    fn constrain(self, mcu: &mut Mcu) -> Pwr;
+}
+
+impl PwrInit for PWR {
+    fn constrain(self, mcu: &mut Mcu) -> Pwr {
+        mcu.rcc.enable(&self);
+        Pwr { pwr: self }
+    }
+}
+
+pub struct Pwr {
+    pwr: PWR,
+}
+
+impl Pwr {
+    /// Enter Sleep mode. It returns after waking up.
+    pub fn sleep(&mut self, entry: SleepEntry, mcu: &mut Mcu) {
+        mcu.scb.scb.clear_sleepdeep();
+        entry.enter();
+    }
+
+    /// When enabled, the MCU goes back to Sleep mode as soon as the lowest priority interrupt
+    /// handler returns, without going back to the thread mode.
+    pub fn set_sleep_on_exit(&mut self, en: bool, mcu: &mut Mcu) {
+        if en {
+            mcu.scb.scb.set_sleeponexit();
+        } else {
+            mcu.scb.scb.clear_sleeponexit();
+        }
+    }
+
+    /// Enter Stop mode. It returns after waking up, and the clock configuration frozen in
+    /// [Rcc](crate::rcc::Rcc) is applied again.
+    ///
+    /// Wake up sources are EXTI lines, configured as interrupts for [SleepEntry::Wfi] or as events
+    /// for [SleepEntry::Wfe].
+    pub fn stop(&mut self, mode: StopMode, entry: SleepEntry, mcu: &mut Mcu) {
+        self.pwr.cr().modify(|_, w| {
+            w.pdds().stop_mode();
+            w.lpds().bit(mode == StopMode::LowPowerRegulator)
+        });
+        mcu.scb.scb.set_sleepdeep();
+        entry.enter();
+        mcu.scb.scb.clear_sleepdeep();
+
+        mcu.rcc.restore_config();
+    }
+
+    /// Enter Standby mode. The MCU will start over from reset after waking up.
+    ///
+    /// Wake up sources are the WKUP pin (see [enable_wakeup_pin](Self::enable_wakeup_pin)),
+    /// the RTC alarm, NRST and IWDG.
+    pub fn standby(&mut self, mcu: &mut Mcu) -> ! {
+        self.pwr
+            .cr()
+            .modify(|_, w| w.pdds().standby_mode().cwuf().set_bit());
+        mcu.scb.scb.set_sleepdeep();
+        loop {
+            SleepEntry::Wfi.enter();
+        }
+    }
+
+    /// Use PA0 as WKUP pin, a rising edge on it wakes up the MCU from Standby mode.
+    /// The pin is consumed, once EWUP is set the hardware takes over PA0 as an input pull-down
+    /// regardless of its GPIO configuration.
+    pub fn enable_wakeup_pin<MODE>(&mut self, _pin: gpio::PA0<MODE>) {
+        self.pwr.csr().modify(|_, w| w.ewup().set_bit());
+    }
+
+    /// Route the RTC alarm to EXTI line 17, so it can wake up the MCU from Stop mode.
+    /// It's not needed for Standby mode.
+    ///
+    /// - `interrupt`: wake up by an interrupt, used with [SleepEntry::Wfi].
+    ///   You will also have to enable the RTCALARM interrupt in the NVIC.
+    /// - `event`: wake up by an event, used with [SleepEntry::Wfe].
+    pub fn listen_rtc_alarm(&mut self, interrupt: bool, event: bool, mcu: &mut Mcu) {
+        let bit = 1 << EXTI_LINE_RTC_ALARM;
+        set_trigger_edge(&mcu.exti, EXTI_LINE_RTC_ALARM, Edge::Rising);
+        mcu.exti
+            .imr()
+            .modify(|r, w| unsafe { w.bits(set_bits(r.bits(), bit, interrupt)) });
+        mcu.exti
+            .emr()
+            .modify(|r, w| unsafe { w.bits(set_bits(r.bits(), bit, event)) });
+    }
+
+    /// Clear the pending bit of EXTI line 17 (RTC alarm).
+    pub fn clear_rtc_alarm_pending_bit(&mut self, mcu: &mut Mcu) {
+        mcu.exti
+            .pr()
+            .write(|w| unsafe { w.bits(1 << EXTI_LINE_RTC_ALARM) });
+    }
+
+    /// Enable the programmable voltage detector with the given threshold.
+    pub fn enable_pvd(&mut self, level: PvdLevel) {
+        self.pwr
+            .cr()
+            .modify(|_, w| unsafe { w.pls().bits(level as u8).pvde().set_bit() });
+    }
+
+    pub fn disable_pvd(&mut self) {
+        self.pwr.cr().modify(|_, w| w.pvde().clear_bit());
+    }
+
+    /// Whether VDD is lower than the PVD threshold.
+    #[inline]
+    pub fn is_below_threshold(&self) -> bool {
+        self.pwr.csr().read().pvdo().bit_is_set()
+    }
+
+    /// Route the PVD output to the EXTI line 16 interrupt.
+    ///
+    /// - [Edge::Rising]: VDD drops below the threshold.
+    /// - [Edge::Falling]: VDD rises above the threshold.
+    ///
+    /// Note, you will also have to enable the PVD interrupt in the NVIC.
+    pub fn listen_pvd(&mut self, edge: Edge, mcu: &mut Mcu) {
+        let bit = 1 << EXTI_LINE_PVD;
+        set_trigger_edge(&mcu.exti, EXTI_LINE_PVD, edge);
+        mcu.exti.pr().write(|w| unsafe { w.bits(bit) });
+        mcu.exti
+            .imr()
+            .modify(|r, w| unsafe { w.bits(r.bits() | bit) });
+    }
+
+    pub fn unlisten_pvd(&mut self, mcu: &mut Mcu) {
+        let bit = 1 << EXTI_LINE_PVD;
+        mcu.exti
+            .imr()
+            .modify(|r, w| unsafe { w.bits(r.bits() & !bit) });
+    }
+
+    pub fn get_pvd_interrupt_handler(&self) -> PvdInterrupt {
+        PvdInterrupt {
+            pwr: unsafe { self.pwr.steal() },
+            exti: unsafe { EXTI::steal() },
+        }
+    }
+
+    /// Whether the MCU has been woken up from Standby mode.
+    pub fn is_standby_flag_set(&self) -> bool {
+        self.pwr.csr().read().sbf().bit_is_set()
+    }
+
+    /// Whether a wakeup event was received from the WKUP pin or the RTC alarm.
+    pub fn is_wakeup_flag_set(&self) -> bool {
+        self.pwr.csr().read().wuf().bit_is_set()
+    }
+
+    /// Clear both the standby and wakeup flags.
+    pub fn clear_flags(&mut self) {
+        self.pwr
+            .cr()
+            .modify(|_, w| w.csbf().set_bit().cwuf().set_bit());
+    }
+}
+
+impl Deref for Pwr {
+    type Target = PWR;
+    fn deref(&self) -> &Self::Target {
+        &self.pwr
+    }
+}
+
+impl DerefMut for Pwr {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.pwr
+    }
+}
+
+/// Can be used in the PVD interrupt callback.
+pub struct PvdInterrupt {
+    pwr: PWR,
+    exti: EXTI,
+}
+
+impl PvdInterrupt {
+    /// Check and clear the pending bit of EXTI line 16.
+    #[inline]
+    pub fn is_interrupted(&mut self) -> bool {
+        let bit = 1 << EXTI_LINE_PVD;
+        if self.exti.pr().read().bits() & bit != 0 {
+            self.exti.pr().write(|w| unsafe { w.bits(bit) });
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether VDD is lower than the PVD threshold.
+    #[inline]
+    pub fn is_below_threshold(&self) -> bool {
+        self.pwr.csr().read().pvdo().bit_is_set()
+    }
+}
+
+// Enumerate ------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SleepEntry {
+    /// Wait for interrupt
+    Wfi,
+    /// Wait for event
+    Wfe,
+}
+
+impl SleepEntry {
+    #[inline(always)]
+    fn enter(self) {
+        match self {
+            SleepEntry::Wfi => cortex_m::asm::wfi(),
+            SleepEntry::Wfe => cortex_m::asm::wfe(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopMode {
+    /// Voltage regulator on, faster wakeup
+    RegulatorOn,
+    /// Voltage regulator in low-power mode, lower consumption
+    LowPowerRegulator,
+}
+
+/// PVD threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PvdLevel {
+    V2_2 = 0,
+    V2_3 = 1,
+    V2_4 = 2,
+    V2_5 = 3,
+    V2_6 = 4,
+    V2_7 = 5,
+    V2_8 = 6,
+    V2_9 = 7,
+}
+
+// Utilities ------------------------------------------------------------------
+
+#[inline(always)]
+const fn set_bits(value: u32, bits: u32, set: bool) -> u32 {
+    if set { value | bits } else { value & !bits }
+}
@@ -27,6 +27,7 @@ impl RccExt for RCC {
         Rcc {
             rb: self,
             clocks: Clocks::default(),
+            cfg: RawConfig::default(),
         }
     }
 }
@@ -43,6 +44,7 @@ impl RccExt for RCC {
 pub struct Rcc {
     pub clocks: Clocks,
     pub(crate) rb: RCC,
+    cfg: RawConfig,
 }
 
 impl Rcc {
@@ -77,6 +79,62 @@ impl Rcc {
             });
         }
 
+        Self::apply_config(&cfg);
+
+        Self {
+            rb: self.rb,
+            clocks,
+            cfg,
+        }
+    }
+
+    /// Applies the frozen clock configuration again.
+    ///
+    /// The HSE and PLL are switched off in Stop mode, and the HSI is selected as the system clock
+    /// after waking up. Call it to get back the clocks in [Clocks].
+    pub fn restore_config(&mut self) {
+        Self::apply_config(&self.cfg);
+    }
+
+    /// Returns the configuration applied by [freeze](Self::freeze).
+    pub fn get_config(&self) -> &RawConfig {
+        &self.cfg
+    }
+
+    /// Switches back to the reset configuration: the HSI is the system clock without prescalers,
+    /// the HSE and PLL are off. The peripheral clocks are not changed.
+    pub fn deinit(&mut self) {
+        let rcc = &self.rb;
+
+        rcc.cr().modify(|_, w| w.hsion().set_bit());
+        while rcc.cr().read().hsirdy().bit_is_clear() {}
+
+        #[allow(unused_unsafe)]
+        rcc.cfgr().modify(|_, w| unsafe { w.sw().bits(0b0) });
+        while rcc.cfgr().read().sws().bits() != 0b0 {}
+        rcc.cfgr().reset();
+
+        rcc.cr()
+            .modify(|_, w| w.hseon().clear_bit().csson().clear_bit().pllon().clear_bit());
+        while rcc.cr().read().pllrdy().bit_is_set() {}
+        rcc.cr().modify(|_, w| w.hsebyp().clear_bit());
+
+        // disable the interrupts and clear the flags
+        rcc.cir().write(|w| {
+            w.lsirdyc().clear();
+            w.lserdyc().clear();
+            w.hsirdyc().clear();
+            w.hserdyc().clear();
+            w.pllrdyc().clear();
+            w.cssc().clear()
+        });
+
+        self.cfg = RawConfig::default();
+        self.clocks = self.cfg.get_clocks();
+    }
+
+    #[inline(always)]
+    fn apply_config(cfg: &RawConfig) {
         let rcc = unsafe { &*RCC::ptr() };
 
         if cfg.hse.is_some() {
@@ -158,11 +216,6 @@ impl Rcc {
                 0b0
             })
         });
-
-        Self {
-            rb: self.rb,
-            clocks,
-        }
     }
 
     pub fn enable<T: Enable>(&mut self, _periph: &T) {
@@ -180,6 +233,39 @@ impl Rcc {
     pub fn get_timer_clock<T: BusTimerClock>(&self, _periph: &T) -> Hertz {
         T::timer_clock(&self.clocks)
     }
+
+    /// Returns the reset flags in RCC_CSR.
+    /// They are kept across resets until [clear_reset_cause](Self::clear_reset_cause) is called,
+    /// so more than one flag may be set.
+    ///
+    /// It can be called before [freeze](Self::freeze).
+    pub fn get_reset_cause(&self) -> ResetCause {
+        ResetCause::from_bits_truncate(self.rb.csr().read().bits())
+    }
+
+    /// Clears all the reset flags in RCC_CSR.
+    pub fn clear_reset_cause(&mut self) {
+        self.rb.csr().modify(|_, w| w.rmvf().clear());
+    }
+}
+
+bitflags::bitflags! {
+    /// Reset flags in RCC_CSR
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ResetCause: u32 {
+        /// NRST pin reset
+        const Pin = 1 << 26;
+        /// Power-on or power-down reset
+        const PowerOn = 1 << 27;
+        /// Software reset
+        const Software = 1 << 28;
+        /// Independent watchdog reset
+        const IndependentWatchdog = 1 << 29;
+        /// Window watchdog reset
+        const WindowWatchdog = 1 << 30;
+        /// Low-power management reset
+        const LowPower = 1 << 31;
+    }
 }
 
 impl Deref for Rcc {
@@ -0,0 +1,39 @@
+//! # Device electronic signature
+//!
+//! Read-only factory data stored in the system memory.
+//!
+//! ```rust
+//! let id: [u8; 12] = signature::unique_id_bytes();
+//! let flash_kb = signature::flash_size_kbytes();
+//! ```
+
+const UID_ADDR: usize = 0x1FFF_F7E8;
+const FLASH_SIZE_ADDR: usize = 0x1FFF_F7E0;
+
+/// 96-bit unique device ID in 3 words, from the lowest address.
+pub fn unique_id() -> [u32; 3] {
+    let ptr = UID_ADDR as *const u32;
+    // NOTE(unsafe) read-only memory that is always valid
+    unsafe {
+        [
+            ptr.read_volatile(),
+            ptr.add(1).read_volatile(),
+            ptr.add(2).read_volatile(),
+        ]
+    }
+}
+
+/// 96-bit unique device ID in 12 bytes, from the lowest address.
+pub fn unique_id_bytes() -> [u8; 12] {
+    let mut bytes = [0; 12];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(unique_id()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Flash memory size in Kbytes
+pub fn flash_size_kbytes() -> u16 {
+    // NOTE(unsafe) read-only memory that is always valid
+    unsafe { (FLASH_SIZE_ADDR as *const u16).read_volatile() }
+}
@@ -1,10 +1,12 @@
 use crate::{
     Mcu, Steal,
     afio::{RemapMode, timer_remap::*},
+    dma::DmaBindTimUp,
     pac::DBGMCU as DBG,
     rcc,
-    time::Hertz,
+    time::{Hertz, MicroSeconds},
 };
+use embedded_hal::digital::InputPin;
 
 pub use crate::common::timer::*;
 
@@ -14,6 +16,8 @@ pub mod monotonic;
 pub use monotonic::*;
 pub mod syst;
 pub use syst::*;
+pub mod trigger;
+pub use trigger::*;
 #[cfg(any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"))]
 pub mod timer1;
 #[cfg(feature = "xl")]
@@ -147,10 +151,52 @@ impl<TIM: Instance + MasterTimer> Timer<TIM> {
     }
 }
 
+impl<TIM: Instance + SlaveTimer> Timer<TIM> {
+    pub fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+        self.tim.set_slave_mode(mode, trigger);
+    }
+
+    /// Use the trigger output of `MASTER` as the trigger input.
+    pub fn set_slave_of<MASTER>(&mut self, mode: SlaveMode)
+    where
+        TIM: InternalTrigger<MASTER>,
+    {
+        self.tim.set_slave_mode(mode, TIM::SOURCE);
+    }
+}
+
+impl<LOW: Instance + MasterTimer> Timer<LOW> {
+    /// Chain two 16-bit timers into a 32-bit counter counting at `tick_freq`, this timer is the
+    /// low half.
+    pub fn chain<HIGH>(
+        mut self,
+        mut high: Timer<HIGH>,
+        tick_freq: Hertz,
+    ) -> ChainedCounter<LOW, HIGH>
+    where
+        HIGH: Instance + SlaveTimer + InternalTrigger<LOW>,
+    {
+        self.config_tick_freq(tick_freq);
+        self.tim.master_mode(MasterMode::Update);
+
+        high.tim.set_prescaler(0);
+        high.tim.set_auto_reload(HIGH::max_auto_reload()).unwrap();
+        high.tim.trigger_update();
+        high.tim
+            .set_slave_mode(SlaveMode::ExternalClock1, HIGH::SOURCE);
+
+        ChainedCounter::new(self.tim, high.tim, self.clk)
+    }
+}
+
 impl<TIM: Instance + TimerDirection> Timer<TIM> {
     pub fn set_count_direction(&mut self, dir: CountDirection) {
         self.tim.set_count_direction(dir);
     }
+
+    pub fn set_align_mode(&mut self, mode: AlignMode) {
+        self.tim.set_align_mode(mode);
+    }
 }
 
 // Initialize PWM -------------------------------------------------------------
@@ -240,6 +286,417 @@ impl<'a, TIM: Instance + TimerWithPwm4Ch + Steal + 'a> Timer<TIM> {
     }
 }
 
+impl<'a, TIM: Instance + TimerWithPwm4Ch + TimerWithComplementaryPwm + Steal + 'a> Timer<TIM> {
+    /// PWM with complementary outputs on the channel 1 to 3.
+    /// A channel is returned if any of its two pins is given.
+    #[allow(clippy::type_complexity)]
+    pub fn into_complementary_pwm<REMAP: RemapMode<TIM>>(
+        mut self,
+        pins: (
+            (
+                Option<impl TimCh1Pin<REMAP>>,
+                Option<impl TimCh1nPin<REMAP>>,
+            ),
+            (
+                Option<impl TimCh2Pin<REMAP>>,
+                Option<impl TimCh2nPin<REMAP>>,
+            ),
+            (
+                Option<impl TimCh3Pin<REMAP>>,
+                Option<impl TimCh3nPin<REMAP>>,
+            ),
+        ),
+        update_freq: Hertz,
+        preload: bool,
+        mcu: &mut Mcu,
+    ) -> (
+        PwmTimer<TIM>,
+        Option<impl ComplementaryPwmChannel + 'a>,
+        Option<impl ComplementaryPwmChannel + 'a>,
+        Option<impl ComplementaryPwmChannel + 'a>,
+    ) {
+        REMAP::remap(&mut mcu.afio);
+        self.tim.enable_preload(preload);
+        self.tim.config_freq(self.clk, update_freq);
+
+        let (p1, p2, p3) = pins;
+        let c1 = (p1.0.is_some() || p1.1.is_some())
+            .then(|| PwmChannel1::new(unsafe { self.tim.steal() }));
+        let c2 = (p2.0.is_some() || p2.1.is_some())
+            .then(|| PwmChannel2::new(unsafe { self.tim.steal() }));
+        let c3 = (p3.0.is_some() || p3.1.is_some())
+            .then(|| PwmChannel3::new(unsafe { self.tim.steal() }));
+        let t = PwmTimer::new(self.tim, self.clk);
+        (t, c1, c2, c3)
+    }
+}
+
+impl<TIM: Instance + TimerWithComplementaryPwm> PwmTimer<TIM> {
+    /// The outputs go to the idle state when the break input is active.
+    pub fn enable_break<REMAP: RemapMode<TIM>>(
+        &mut self,
+        _pin: impl TimBkinPin<REMAP>,
+        polarity: BreakPolarity,
+    ) {
+        self.tim.set_break(Some(polarity));
+    }
+}
+
+impl<TIM: Instance + TimerWithPwm + TimerWithDma + Steal> PwmTimer<TIM> {
+    /// Stream duty values into `channel` by a DMA channel, see [PwmDma].
+    pub fn pwm_dma<CH: DmaBindTimUp<TIM>>(
+        &mut self,
+        dma_ch: CH,
+        channel: Channel,
+    ) -> PwmDma<TIM, CH> {
+        PwmDma::new(unsafe { self.tim.steal() }, dma_ch, channel)
+    }
+}
+
+// Initialize One Pulse -------------------------------------------------------
+
+impl<TIM: Instance + TimerWithPwm2Ch + TimerWithInputCapture + SlaveTimer> Timer<TIM> {
+    /// Output a pulse on channel 1. The prescaler is the smallest one that a delay plus a
+    /// width of `max_len` doesn't overflow the counter.
+    pub fn into_one_pulse<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pin: impl TimCh1Pin<REMAP>,
+        max_len: MicroSeconds,
+        mcu: &mut Mcu,
+    ) -> OnePulse<TIM> {
+        REMAP::remap(&mut mcu.afio);
+        self.config_one_pulse(Channel::C1, max_len);
+        OnePulse::new(self.tim, self.clk, Channel::C1)
+    }
+
+    /// Output a pulse on channel 1 at each `edge` of channel 2.
+    pub fn into_one_pulse_ti2<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pins: (impl TimCh1Pin<REMAP>, impl TimCh2InPin<REMAP>),
+        edge: CaptureEdge,
+        filter: InputFilter,
+        max_len: MicroSeconds,
+        mcu: &mut Mcu,
+    ) -> OnePulse<TIM> {
+        REMAP::remap(&mut mcu.afio);
+        self.config_one_pulse(Channel::C1, max_len);
+        self.config_trigger_input(Channel::C2, edge, filter);
+        let mut pulse = OnePulse::new(self.tim, self.clk, Channel::C1);
+        pulse.set_trigger(Some(TriggerSource::Ti2Fp2));
+        pulse
+    }
+
+    /// Output a pulse on channel 2 at each `edge` of channel 1.
+    pub fn into_one_pulse_ti1<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pins: (impl TimCh1InPin<REMAP>, impl TimCh2Pin<REMAP>),
+        edge: CaptureEdge,
+        filter: InputFilter,
+        max_len: MicroSeconds,
+        mcu: &mut Mcu,
+    ) -> OnePulse<TIM> {
+        REMAP::remap(&mut mcu.afio);
+        self.config_one_pulse(Channel::C2, max_len);
+        self.config_trigger_input(Channel::C1, edge, filter);
+        let mut pulse = OnePulse::new(self.tim, self.clk, Channel::C2);
+        pulse.set_trigger(Some(TriggerSource::Ti1Fp1));
+        pulse
+    }
+
+    fn config_one_pulse(&mut self, channel: Channel, max_len: MicroSeconds) {
+        let max_ticks = TIM::max_auto_reload() as u64 + 1;
+        let psc = (self.clk.raw() as u64 * max_len.ticks() as u64)
+            .div_ceil(1_000_000 * max_ticks)
+            .max(1);
+        assert!(psc <= 1 << 16);
+        self.tim.set_prescaler((psc - 1) as u16);
+        self.tim.set_update_on_overflow_only(true);
+        self.tim
+            .preload_output_channel_in_mode(channel, PwmMode::Mode2);
+        match channel {
+            Channel::C1 => self.tim.enable_ch1(true),
+            _ => self.tim.enable_ch2(true),
+        }
+    }
+
+    fn config_trigger_input(&mut self, channel: Channel, edge: CaptureEdge, filter: InputFilter) {
+        self.tim.config_input_capture(
+            channel,
+            CaptureInput::Direct,
+            CapturePrescaler::Div1,
+            filter,
+        );
+        self.tim.set_capture_edge(channel, edge);
+    }
+}
+
+impl<TIM: Instance + TimerWithPwm2Ch + SlaveTimer> OnePulse<TIM> {
+    /// Start a pulse at each `edge` of ETR.
+    pub fn set_etr_trigger<REMAP: RemapMode<TIM>>(
+        &mut self,
+        _pin: impl TimEtrPin<REMAP>,
+        edge: CaptureEdge,
+        filter: InputFilter,
+    ) {
+        self.tim
+            .config_external_trigger(edge, CapturePrescaler::Div1, filter);
+        self.set_trigger(Some(TriggerSource::Etrf));
+    }
+}
+
+// Initialize Input Capture ---------------------------------------------------
+
+impl<TIM: Instance> Timer<TIM> {
+    /// The counter counts at `tick_freq` and wraps around at the maximum value.
+    fn config_tick_freq(&mut self, tick_freq: Hertz) {
+        let psc = self.clk.raw() / tick_freq.raw();
+        assert!(psc >= 1 && psc - 1 <= u16::MAX as u32);
+        self.tim.set_prescaler((psc - 1) as u16);
+        self.tim.set_auto_reload(TIM::max_auto_reload()).unwrap();
+        self.tim.trigger_update();
+    }
+}
+
+impl<'a, TIM: Instance + TimerWithPwm1Ch + TimerWithInputCapture + Steal + 'a> Timer<TIM> {
+    pub fn into_capture1<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pin: impl TimCh1InPin<REMAP>,
+        tick_freq: Hertz,
+        mcu: &mut Mcu,
+    ) -> (CaptureTimer<TIM>, impl CaptureChannel + 'a) {
+        REMAP::remap(&mut mcu.afio);
+        self.config_tick_freq(tick_freq);
+
+        let c1 = CaptureChannel1::new(unsafe { self.tim.steal() });
+        let t = CaptureTimer::new(self.tim, self.clk);
+        (t, c1)
+    }
+}
+
+impl<'a, TIM: Instance + TimerWithPwm2Ch + TimerWithInputCapture + Steal + 'a> Timer<TIM> {
+    pub fn into_capture2<REMAP: RemapMode<TIM>>(
+        mut self,
+        pins: (
+            Option<impl TimCh1InPin<REMAP>>,
+            Option<impl TimCh2InPin<REMAP>>,
+        ),
+        tick_freq: Hertz,
+        mcu: &mut Mcu,
+    ) -> (
+        CaptureTimer<TIM>,
+        Option<impl CaptureChannel + 'a>,
+        Option<impl CaptureChannel + 'a>,
+    ) {
+        REMAP::remap(&mut mcu.afio);
+        self.config_tick_freq(tick_freq);
+
+        let c1 = pins
+            .0
+            .map(|_| CaptureChannel1::new(unsafe { self.tim.steal() }));
+        let c2 = pins
+            .1
+            .map(|_| CaptureChannel2::new(unsafe { self.tim.steal() }));
+        let t = CaptureTimer::new(self.tim, self.clk);
+        (t, c1, c2)
+    }
+}
+
+impl<'a, TIM: Instance + TimerWithPwm4Ch + TimerWithInputCapture + Steal + 'a> Timer<TIM> {
+    pub fn into_capture4<REMAP: RemapMode<TIM>>(
+        mut self,
+        pins: (
+            Option<impl TimCh1InPin<REMAP>>,
+            Option<impl TimCh2InPin<REMAP>>,
+            Option<impl TimCh3InPin<REMAP>>,
+            Option<impl TimCh4InPin<REMAP>>,
+        ),
+        tick_freq: Hertz,
+        mcu: &mut Mcu,
+    ) -> (
+        CaptureTimer<TIM>,
+        Option<impl CaptureChannel + 'a>,
+        Option<impl CaptureChannel + 'a>,
+        Option<impl CaptureChannel + 'a>,
+        Option<impl CaptureChannel + 'a>,
+    ) {
+        REMAP::remap(&mut mcu.afio);
+        self.config_tick_freq(tick_freq);
+
+        let c1 = pins
+            .0
+            .map(|_| CaptureChannel1::new(unsafe { self.tim.steal() }));
+        let c2 = pins
+            .1
+            .map(|_| CaptureChannel2::new(unsafe { self.tim.steal() }));
+        let c3 = pins
+            .2
+            .map(|_| CaptureChannel3::new(unsafe { self.tim.steal() }));
+        let c4 = pins
+            .3
+            .map(|_| CaptureChannel4::new(unsafe { self.tim.steal() }));
+        let t = CaptureTimer::new(self.tim, self.clk);
+        (t, c1, c2, c3, c4)
+    }
+}
+
+// Initialize PWM Input -------------------------------------------------------
+
+impl<TIM: Instance + TimerWithPwm2Ch + TimerWithInputCapture + SlaveTimer> Timer<TIM> {
+    /// Measure the signal on channel 1, whose frequency is expected from `min_freq` to
+    /// `max_freq`. The prescaler is the smallest one that a period of `min_freq` doesn't
+    /// overflow the counter.
+    pub fn into_pwm_input<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pin: impl TimCh1InPin<REMAP>,
+        min_freq: Hertz,
+        max_freq: Hertz,
+        mcu: &mut Mcu,
+    ) -> PwmInput<TIM> {
+        assert!(min_freq.raw() > 0 && min_freq <= max_freq);
+        REMAP::remap(&mut mcu.afio);
+
+        let max_ticks = TIM::max_auto_reload() as u64 + 1;
+        let psc = (self.clk.raw() as u64)
+            .div_ceil(min_freq.raw() as u64 * max_ticks)
+            .max(1);
+        assert!(psc <= 1 << 16);
+        // At least 2 ticks in a period to measure the duty cycle
+        assert!(self.clk.raw() as u64 / psc >= max_freq.raw() as u64 * 2);
+        self.tim.set_prescaler((psc - 1) as u16);
+        self.tim.set_auto_reload(TIM::max_auto_reload()).unwrap();
+        self.tim.trigger_update();
+        // The counter reset by the slave mode controller must not be taken as an overflow.
+        self.tim.set_update_on_overflow_only(true);
+
+        self.tim.config_input_capture(
+            Channel::C1,
+            CaptureInput::Direct,
+            CapturePrescaler::Div1,
+            InputFilter::NoFilter,
+        );
+        self.tim.set_capture_edge(Channel::C1, CaptureEdge::Rising);
+        self.tim.config_input_capture(
+            Channel::C2,
+            CaptureInput::Indirect,
+            CapturePrescaler::Div1,
+            InputFilter::NoFilter,
+        );
+        self.tim.set_capture_edge(Channel::C2, CaptureEdge::Falling);
+        self.tim
+            .set_slave_mode(SlaveMode::Reset, TriggerSource::Ti1Fp1);
+        self.tim.enable_ch1(true);
+        self.tim.enable_ch2(true);
+
+        PwmInput::new(self.tim, self.clk)
+    }
+}
+
+// Initialize QEI -------------------------------------------------------------
+
+impl<TIM: Instance + TimerWithPwm2Ch + TimerWithInputCapture + SlaveTimer + TimerDirection>
+    Timer<TIM>
+{
+    /// Count the quadrature encoder on channel 1 and 2. The counter starts at 0.
+    pub fn into_qei<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pins: (impl TimCh1InPin<REMAP>, impl TimCh2InPin<REMAP>),
+        mode: QeiMode,
+        filter: InputFilter,
+        mcu: &mut Mcu,
+    ) -> Qei<TIM> {
+        REMAP::remap(&mut mcu.afio);
+
+        self.tim.set_prescaler(0);
+        self.tim.set_auto_reload(TIM::max_auto_reload()).unwrap();
+        self.tim.trigger_update();
+
+        for channel in [Channel::C1, Channel::C2] {
+            self.tim.config_input_capture(
+                channel,
+                CaptureInput::Direct,
+                CapturePrescaler::Div1,
+                filter,
+            );
+            self.tim.set_capture_edge(channel, CaptureEdge::Rising);
+        }
+        self.tim.set_slave_mode(mode.into(), TriggerSource::Itr0);
+        self.tim.reset_counter();
+        self.tim.enable_counter();
+
+        Qei::new(self.tim)
+    }
+}
+
+// Initialize Hall Sensor -----------------------------------------------------
+
+impl<TIM: Instance + TimerWithPwm4Ch + TimerWithInputCapture + SlaveTimer + MasterTimer>
+    Timer<TIM>
+{
+    /// Hall sensor interface with the inputs on channel 1 to 3. The interval between two edges
+    /// is measured at `tick_freq`, and the trigger output sends a pulse at each edge.
+    pub fn into_hall_sensor<REMAP, H1, H2, H3>(
+        mut self,
+        pins: (H1, H2, H3),
+        filter: InputFilter,
+        tick_freq: Hertz,
+        mcu: &mut Mcu,
+    ) -> HallSensor<TIM, H1, H2, H3>
+    where
+        REMAP: RemapMode<TIM>,
+        H1: TimCh1InPin<REMAP> + InputPin,
+        H2: TimCh2InPin<REMAP> + InputPin,
+        H3: TimCh3InPin<REMAP> + InputPin,
+    {
+        REMAP::remap(&mut mcu.afio);
+        self.config_tick_freq(tick_freq);
+        // The counter reset by the slave mode controller must not be taken as an overflow.
+        self.tim.set_update_on_overflow_only(true);
+
+        self.tim.set_xor_input(true);
+        self.tim.config_input_capture(
+            Channel::C1,
+            CaptureInput::Trc,
+            CapturePrescaler::Div1,
+            filter,
+        );
+        self.tim
+            .set_slave_mode(SlaveMode::Reset, TriggerSource::Ti1FEd);
+        self.tim.master_mode(MasterMode::ComparePulse);
+        self.tim.enable_ch1(true);
+
+        HallSensor::new(self.tim, self.clk, pins)
+    }
+}
+
+// Initialize Pulse Counter ---------------------------------------------------
+
+impl<TIM: Instance + SlaveTimer> Timer<TIM> {
+    /// Count the pulses on ETR. `edge` selects the counted edge, and `prescaler` divides the
+    /// pulses before they're counted, the ETR frequency after the prescaler must be less than a
+    /// quarter of the timer clock.
+    pub fn into_pulse_counter<REMAP: RemapMode<TIM>>(
+        mut self,
+        _pin: impl TimEtrPin<REMAP>,
+        edge: CaptureEdge,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+        mcu: &mut Mcu,
+    ) -> PulseCounter<TIM> {
+        REMAP::remap(&mut mcu.afio);
+
+        self.tim.set_prescaler(0);
+        self.tim.set_auto_reload(TIM::max_auto_reload()).unwrap();
+        self.tim.trigger_update();
+
+        self.tim.config_external_trigger(edge, prescaler, filter);
+        self.tim.set_external_clock_mode2(true);
+
+        let mut counter = PulseCounter::new(self.tim);
+        counter.reset();
+        counter
+    }
+}
+
 // Destroy --------------------------------------------------------------------
 
 pub fn destroy_counter_hz<TIM: GeneralTimer>(mut counter: CounterHz<TIM>) -> Timer<TIM> {
@@ -291,3 +748,9 @@ const fn compute_prescaler_arr(timer_clk: u32, update_freq: u32) -> (u32, u32) {
     let arr = ticks / (prescaler + 1) - 1;
     (prescaler, arr)
 }
+
+/// CCxOF in the status register
+#[inline(always)]
+const fn overcapture_flag(channel: Channel) -> u32 {
+    1 << (9 + channel as u32)
+}
@@ -95,6 +95,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.urs().clear_bit());
     }
 
+    #[inline(always)]
+    fn set_update_on_overflow_only(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.urs().bit(b));
+    }
+
     #[inline]
     fn config_freq(&mut self, clock: Hertz, update_freq: Hertz) {
         let (prescaler, arr) = compute_prescaler_arr(clock.raw(), update_freq.raw());
@@ -131,6 +136,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.opm().set_bit().cen().set_bit());
     }
 
+    #[inline(always)]
+    fn set_one_pulse_mode(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.opm().bit(b));
+    }
+
     #[inline(always)]
     fn stop_in_debug(&mut self, state: bool) {
         let dbg = unsafe { DBG::steal() };
@@ -283,6 +293,82 @@ impl TimerWithPwm4Ch for TimerX {
     }
 }
 
+// sync capture_4
+// Input Capture --------------------------------------------------------------
+
+impl TimerWithInputCapture for TimerX {
+    fn config_input_capture(
+        &mut self,
+        channel: Channel,
+        input: CaptureInput,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    ) {
+        match channel {
+            Channel::C1 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc1s().bits(input as u8) };
+                    w.ic1psc().set(prescaler as u8);
+                    w.ic1f().set(filter as u8)
+                });
+            }
+            Channel::C2 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc2s().bits(input as u8) };
+                    w.ic2psc().set(prescaler as u8);
+                    w.ic2f().set(filter as u8)
+                });
+            }
+            Channel::C3 => {
+                self.ccmr2_input().modify(|_, w| {
+                    unsafe { w.cc3s().bits(input as u8) };
+                    w.ic3psc().set(prescaler as u8);
+                    w.ic3f().set(filter as u8)
+                });
+            }
+            Channel::C4 => {
+                self.ccmr2_input().modify(|_, w| {
+                    unsafe { w.cc4s().bits(input as u8) };
+                    w.ic4psc().set(prescaler as u8);
+                    w.ic4f().set(filter as u8)
+                });
+            }
+        }
+    }
+
+    fn set_capture_edge(&mut self, channel: Channel, edge: CaptureEdge) {
+        match channel {
+            Channel::C1 => {
+                self.ccer()
+                    .modify(|_, w| w.cc1p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C2 => {
+                self.ccer()
+                    .modify(|_, w| w.cc2p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C3 => {
+                self.ccer()
+                    .modify(|_, w| w.cc3p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C4 => {
+                self.ccer()
+                    .modify(|_, w| w.cc4p().bit(edge == CaptureEdge::Falling));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn is_overcaptured(&self, channel: Channel) -> bool {
+        self.sr().read().bits() & overcapture_flag(channel) != 0
+    }
+
+    #[inline(always)]
+    fn clear_overcapture(&mut self, channel: Channel) {
+        self.sr()
+            .write(|w| unsafe { w.bits(0xffff & !overcapture_flag(channel)) });
+    }
+}
+
 // Other ----------------------------------------------------------------------
 
 // sync master
@@ -293,6 +379,43 @@ impl MasterTimer for TimerX {
     }
 }
 
+// sync slave
+
+impl SlaveTimer for TimerX {
+    #[inline(always)]
+    fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+        // TS must only be changed while the slave mode is disabled.
+        self.smcr().modify(|_, w| w.sms().set(0));
+        self.smcr()
+            .modify(|_, w| unsafe { w.ts().bits(trigger as u8) });
+        self.smcr().modify(|_, w| w.sms().set(mode as u8));
+    }
+
+    #[inline(always)]
+    fn set_xor_input(&mut self, en: bool) {
+        self.cr2().modify(|_, w| w.ti1s().bit(en));
+    }
+
+    #[inline(always)]
+    fn config_external_trigger(
+        &mut self,
+        edge: CaptureEdge,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    ) {
+        self.smcr().modify(|_, w| {
+            w.etp().bit(edge == CaptureEdge::Falling);
+            w.etps().set(prescaler as u8);
+            w.etf().set(filter as u8)
+        });
+    }
+
+    #[inline(always)]
+    fn set_external_clock_mode2(&mut self, en: bool) {
+        self.smcr().modify(|_, w| w.ece().bit(en));
+    }
+}
+
 // sync dir
 
 impl TimerDirection for TimerX {
@@ -301,6 +424,56 @@ impl TimerDirection for TimerX {
         self.cr1()
             .modify(|_, w| w.dir().bit(dir == CountDirection::Down));
     }
+
+    #[inline(always)]
+    fn get_count_direction(&self) -> CountDirection {
+        if self.cr1().read().dir().bit_is_set() {
+            CountDirection::Down
+        } else {
+            CountDirection::Up
+        }
+    }
+
+    #[inline(always)]
+    fn set_align_mode(&mut self, mode: AlignMode) {
+        self.cr1().modify(|_, w| w.cms().set(mode as u8));
+    }
+}
+
+// sync dma
+
+impl TimerWithDma for TimerX {
+    // sync dma_req
+
+    #[inline(always)]
+    fn listen_dma_request(&mut self, event: Event, b: bool) {
+        // UDE and CC1DE to CC4DE are 8 bits above the interrupt enable bits.
+        let bits = (event & (Event::Update | Event::C1 | Event::C2 | Event::C3 | Event::C4))
+            .bits()
+            << 8;
+        self.dier()
+            .modify(|r, w| unsafe { w.bits(if b { r.bits() | bits } else { r.bits() & !bits }) });
+    }
+
+    // sync dma_burst
+
+    #[inline(always)]
+    fn config_dma_burst(&mut self, base: u8, len: u8) {
+        assert!((1..=18).contains(&len));
+        assert!(base <= 18 - len);
+        self.dcr()
+            .write(|w| unsafe { w.dba().set(base).dbl().bits(len - 1) });
+    }
+
+    #[inline(always)]
+    fn dma_burst_address(&self) -> usize {
+        self.dmar().as_ptr() as usize
+    }
+
+    #[inline(always)]
+    fn cc_address(&self, channel: Channel) -> usize {
+        self.ccr(channel as usize).as_ptr() as usize
+    }
 }
 
 // sync RTIC
@@ -95,6 +95,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.urs().clear_bit());
     }
 
+    #[inline(always)]
+    fn set_update_on_overflow_only(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.urs().bit(b));
+    }
+
     #[inline]
     fn config_freq(&mut self, clock: Hertz, update_freq: Hertz) {
         let (prescaler, arr) = compute_prescaler_arr(clock.raw(), update_freq.raw());
@@ -131,6 +136,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.opm().set_bit().cen().set_bit());
     }
 
+    #[inline(always)]
+    fn set_one_pulse_mode(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.opm().bit(b));
+    }
+
     #[inline(always)]
     fn stop_in_debug(&mut self, state: bool) {
         let dbg = unsafe { DBG::steal() };
@@ -153,12 +163,11 @@ impl TimerWithPwm for TimerX {
         self.disable_counter();
     }
 
-    // sync start_pwm_aoe
+    // sync start_pwm_moe
 
     #[inline(always)]
     fn start_pwm(&mut self) {
-        // self.bdtr().modify(|_, w| w.moe().set_bit());
-        self.bdtr().modify(|_, w| w.aoe().set_bit());
+        self.bdtr().modify(|_, w| w.moe().set_bit());
         self.reset_counter();
         self.enable_counter();
     }
@@ -285,6 +294,82 @@ impl TimerWithPwm4Ch for TimerX {
     }
 }
 
+// sync capture_4
+// Input Capture --------------------------------------------------------------
+
+impl TimerWithInputCapture for TimerX {
+    fn config_input_capture(
+        &mut self,
+        channel: Channel,
+        input: CaptureInput,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    ) {
+        match channel {
+            Channel::C1 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc1s().bits(input as u8) };
+                    w.ic1psc().set(prescaler as u8);
+                    w.ic1f().set(filter as u8)
+                });
+            }
+            Channel::C2 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc2s().bits(input as u8) };
+                    w.ic2psc().set(prescaler as u8);
+                    w.ic2f().set(filter as u8)
+                });
+            }
+            Channel::C3 => {
+                self.ccmr2_input().modify(|_, w| {
+                    unsafe { w.cc3s().bits(input as u8) };
+                    w.ic3psc().set(prescaler as u8);
+                    w.ic3f().set(filter as u8)
+                });
+            }
+            Channel::C4 => {
+                self.ccmr2_input().modify(|_, w| {
+                    unsafe { w.cc4s().bits(input as u8) };
+                    w.ic4psc().set(prescaler as u8);
+                    w.ic4f().set(filter as u8)
+                });
+            }
+        }
+    }
+
+    fn set_capture_edge(&mut self, channel: Channel, edge: CaptureEdge) {
+        match channel {
+            Channel::C1 => {
+                self.ccer()
+                    .modify(|_, w| w.cc1p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C2 => {
+                self.ccer()
+                    .modify(|_, w| w.cc2p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C3 => {
+                self.ccer()
+                    .modify(|_, w| w.cc3p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C4 => {
+                self.ccer()
+                    .modify(|_, w| w.cc4p().bit(edge == CaptureEdge::Falling));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn is_overcaptured(&self, channel: Channel) -> bool {
+        self.sr().read().bits() & overcapture_flag(channel) != 0
+    }
+
+    #[inline(always)]
+    fn clear_overcapture(&mut self, channel: Channel) {
+        self.sr()
+            .write(|w| unsafe { w.bits(0xffff & !overcapture_flag(channel)) });
+    }
+}
+
 // Other ----------------------------------------------------------------------
 
 // sync master
@@ -295,6 +380,43 @@ impl MasterTimer for TimerX {
     }
 }
 
+// sync slave
+
+impl SlaveTimer for TimerX {
+    #[inline(always)]
+    fn set_slave_mode(&mut self, mode: SlaveMode, trigger: TriggerSource) {
+        // TS must only be changed while the slave mode is disabled.
+        self.smcr().modify(|_, w| w.sms().set(0));
+        self.smcr()
+            .modify(|_, w| unsafe { w.ts().bits(trigger as u8) });
+        self.smcr().modify(|_, w| w.sms().set(mode as u8));
+    }
+
+    #[inline(always)]
+    fn set_xor_input(&mut self, en: bool) {
+        self.cr2().modify(|_, w| w.ti1s().bit(en));
+    }
+
+    #[inline(always)]
+    fn config_external_trigger(
+        &mut self,
+        edge: CaptureEdge,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    ) {
+        self.smcr().modify(|_, w| {
+            w.etp().bit(edge == CaptureEdge::Falling);
+            w.etps().set(prescaler as u8);
+            w.etf().set(filter as u8)
+        });
+    }
+
+    #[inline(always)]
+    fn set_external_clock_mode2(&mut self, en: bool) {
+        self.smcr().modify(|_, w| w.ece().bit(en));
+    }
+}
+
 // sync dir
 
 impl TimerDirection for TimerX {
@@ -303,6 +425,57 @@ impl TimerDirection for TimerX {
         self.cr1()
             .modify(|_, w| w.dir().bit(dir == CountDirection::Down));
     }
+
+    #[inline(always)]
+    fn get_count_direction(&self) -> CountDirection {
+        if self.cr1().read().dir().bit_is_set() {
+            CountDirection::Down
+        } else {
+            CountDirection::Up
+        }
+    }
+
+    #[inline(always)]
+    fn set_align_mode(&mut self, mode: AlignMode) {
+        self.cr1().modify(|_, w| w.cms().set(mode as u8));
+    }
+}
+
+// sync dma
+
+impl TimerWithDma for TimerX {
+    // sync dma_req_com
+
+    #[inline(always)]
+    fn listen_dma_request(&mut self, event: Event, b: bool) {
+        // UDE, CC1DE to CC4DE and COMDE are 8 bits above the interrupt enable bits.
+        let bits = (event
+            & (Event::Update | Event::C1 | Event::C2 | Event::C3 | Event::C4 | Event::Com))
+            .bits()
+            << 8;
+        self.dier()
+            .modify(|r, w| unsafe { w.bits(if b { r.bits() | bits } else { r.bits() & !bits }) });
+    }
+
+    // sync dma_burst
+
+    #[inline(always)]
+    fn config_dma_burst(&mut self, base: u8, len: u8) {
+        assert!((1..=18).contains(&len));
+        assert!(base <= 18 - len);
+        self.dcr()
+            .write(|w| unsafe { w.dba().set(base).dbl().bits(len - 1) });
+    }
+
+    #[inline(always)]
+    fn dma_burst_address(&self) -> usize {
+        self.dmar().as_ptr() as usize
+    }
+
+    #[inline(always)]
+    fn cc_address(&self, channel: Channel) -> usize {
+        self.ccr(channel as usize).as_ptr() as usize
+    }
 }
 
 // sync RTIC
@@ -408,4 +581,118 @@ mod timer_rtic {
     }
 }
 
+// sync complementary
+// Complementary PWM ----------------------------------------------------------
+
+impl TimerWithComplementaryPwm for TimerX {
+    fn enable_complementary(&mut self, channel: Channel, en: bool) {
+        match channel {
+            Channel::C1 => {
+                self.ccer().modify(|_, w| w.cc1ne().bit(en));
+            }
+            Channel::C2 => {
+                self.ccer().modify(|_, w| w.cc2ne().bit(en));
+            }
+            Channel::C3 => {
+                self.ccer().modify(|_, w| w.cc3ne().bit(en));
+            }
+            _ => (),
+        }
+    }
+
+    fn set_complementary_polarity(&mut self, channel: Channel, polarity: PwmPolarity) {
+        match channel {
+            Channel::C1 => {
+                self.ccer()
+                    .modify(|_, w| w.cc1np().bit(polarity == PwmPolarity::ActiveLow));
+            }
+            Channel::C2 => {
+                self.ccer()
+                    .modify(|_, w| w.cc2np().bit(polarity == PwmPolarity::ActiveLow));
+            }
+            Channel::C3 => {
+                self.ccer()
+                    .modify(|_, w| w.cc3np().bit(polarity == PwmPolarity::ActiveLow));
+            }
+            _ => (),
+        }
+    }
+
+    fn set_idle_state(&mut self, channel: Channel, output: bool, complementary: bool) {
+        match channel {
+            Channel::C1 => {
+                self.cr2()
+                    .modify(|_, w| w.ois1().bit(output).ois1n().bit(complementary));
+            }
+            Channel::C2 => {
+                self.cr2()
+                    .modify(|_, w| w.ois2().bit(output).ois2n().bit(complementary));
+            }
+            Channel::C3 => {
+                self.cr2()
+                    .modify(|_, w| w.ois3().bit(output).ois3n().bit(complementary));
+            }
+            Channel::C4 => {
+                self.cr2().modify(|_, w| w.ois4().bit(output));
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn set_dead_time_bits(&mut self, dtg: u8) {
+        self.bdtr().modify(|_, w| w.dtg().set(dtg));
+    }
+
+    #[inline(always)]
+    fn set_break(&mut self, polarity: Option<BreakPolarity>) {
+        self.bdtr().modify(|_, w| match polarity {
+            Some(p) => w.bke().set_bit().bkp().bit(p == BreakPolarity::ActiveHigh),
+            None => w.bke().clear_bit(),
+        });
+    }
+
+    #[inline(always)]
+    fn set_automatic_output(&mut self, en: bool) {
+        self.bdtr().modify(|_, w| w.aoe().bit(en));
+    }
+
+    #[inline(always)]
+    fn set_off_state(&mut self, run: bool, idle: bool) {
+        self.bdtr()
+            .modify(|_, w| w.ossr().bit(run).ossi().bit(idle));
+    }
+
+    #[inline(always)]
+    fn enable_main_output(&mut self, en: bool) {
+        self.bdtr().modify(|_, w| w.moe().bit(en));
+    }
+
+    #[inline(always)]
+    fn is_main_output_enabled(&self) -> bool {
+        self.bdtr().read().moe().bit_is_set()
+    }
+
+    #[inline(always)]
+    fn set_repetition_counter(&mut self, rep: u8) {
+        self.rcr().write(|w| w.rep().set(rep));
+    }
+
+    #[inline(always)]
+    fn set_commutation(&mut self, update: Option<CommutationUpdate>) {
+        self.cr2().modify(|_, w| match update {
+            Some(u) => w
+                .ccpc()
+                .set_bit()
+                .ccus()
+                .bit(u == CommutationUpdate::SoftwareOrTrigger),
+            None => w.ccpc().clear_bit(),
+        });
+    }
+
+    #[inline(always)]
+    fn trigger_commutation(&mut self) {
+        self.egr().write(|w| w.comg().set_bit());
+    }
+}
+
 // sync end
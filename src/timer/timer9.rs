@@ -94,6 +94,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.urs().clear_bit());
     }
 
+    #[inline(always)]
+    fn set_update_on_overflow_only(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.urs().bit(b));
+    }
+
     #[inline]
     fn config_freq(&mut self, clock: Hertz, update_freq: Hertz) {
         let (prescaler, arr) = compute_prescaler_arr(clock.raw(), update_freq.raw());
@@ -130,6 +135,11 @@ impl GeneralTimer for TimerX {
         self.cr1().modify(|_, w| w.opm().set_bit().cen().set_bit());
     }
 
+    #[inline(always)]
+    fn set_one_pulse_mode(&mut self, b: bool) {
+        self.cr1().modify(|_, w| w.opm().bit(b));
+    }
+
     #[inline(always)]
     fn stop_in_debug(&mut self, state: bool) {
         let dbg = unsafe { DBG::steal() };
@@ -233,4 +243,60 @@ impl TimerWithPwm2Ch for TimerX {
     }
 }
 
+// sync capture_2
+// Input Capture --------------------------------------------------------------
+
+impl TimerWithInputCapture for TimerX {
+    fn config_input_capture(
+        &mut self,
+        channel: Channel,
+        input: CaptureInput,
+        prescaler: CapturePrescaler,
+        filter: InputFilter,
+    ) {
+        match channel {
+            Channel::C1 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc1s().bits(input as u8) };
+                    w.ic1psc().set(prescaler as u8);
+                    w.ic1f().set(filter as u8)
+                });
+            }
+            Channel::C2 => {
+                self.ccmr1_input().modify(|_, w| {
+                    unsafe { w.cc2s().bits(input as u8) };
+                    w.ic2psc().set(prescaler as u8);
+                    w.ic2f().set(filter as u8)
+                });
+            }
+            _ => (),
+        }
+    }
+
+    fn set_capture_edge(&mut self, channel: Channel, edge: CaptureEdge) {
+        match channel {
+            Channel::C1 => {
+                self.ccer()
+                    .modify(|_, w| w.cc1p().bit(edge == CaptureEdge::Falling));
+            }
+            Channel::C2 => {
+                self.ccer()
+                    .modify(|_, w| w.cc2p().bit(edge == CaptureEdge::Falling));
+            }
+            _ => (),
+        }
+    }
+
+    #[inline(always)]
+    fn is_overcaptured(&self, channel: Channel) -> bool {
+        self.sr().read().bits() & overcapture_flag(channel) != 0
+    }
+
+    #[inline(always)]
+    fn clear_overcapture(&mut self, channel: Channel) {
+        self.sr()
+            .write(|w| unsafe { w.bits(0xffff & !overcapture_flag(channel)) });
+    }
+}
+
 // sync end
@@ -0,0 +1,268 @@
+//! Internal trigger connections between the timers.
+
+use super::TriggerSource;
+use crate::pac;
+
+/// The trigger output (TRGO) of `MASTER` is connected to an internal trigger input of this timer.
+pub trait InternalTrigger<MASTER> {
+    const SOURCE: TriggerSource;
+}
+
+// table
+// Do NOT manually modify the code.
+// It's generated by scripts/generate_trigger_table.py from scripts/table/stm32f1_timer_trigger.csv
+
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"),
+    any(feature = "high", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM5> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM2> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM3> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"),
+    feature = "medium"
+))]
+impl InternalTrigger<pac::TIM4> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    all(feature = "stm32f103", feature = "high")
+))]
+impl InternalTrigger<pac::TIM8> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(not(feature = "stm32f100"))]
+impl InternalTrigger<pac::TIM3> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(not(feature = "stm32f100"), feature = "medium"))]
+impl InternalTrigger<pac::TIM4> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(not(feature = "stm32f100"))]
+impl InternalTrigger<pac::TIM2> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "high", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM5> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(not(feature = "stm32f100"), feature = "medium"))]
+impl InternalTrigger<pac::TIM4> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    feature = "medium",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(not(feature = "stm32f100"), feature = "medium"))]
+impl InternalTrigger<pac::TIM2> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(not(feature = "stm32f100"), feature = "medium"))]
+impl InternalTrigger<pac::TIM3> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    feature = "medium",
+    all(feature = "stm32f103", feature = "high")
+))]
+impl InternalTrigger<pac::TIM8> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "high", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM2> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "high", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM3> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "high", feature = "connectivity"),
+    feature = "medium"
+))]
+impl InternalTrigger<pac::TIM4> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    not(feature = "stm32f100"),
+    any(feature = "high", feature = "connectivity"),
+    all(feature = "stm32f103", feature = "high")
+))]
+impl InternalTrigger<pac::TIM8> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"),
+    feature = "stm32f100"
+))]
+impl InternalTrigger<pac::TIM15> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM2> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM3> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity"),
+    feature = "medium"
+))]
+impl InternalTrigger<pac::TIM4> for pac::TIM1 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(feature = "stm32f100", feature = "stm32f100"))]
+impl InternalTrigger<pac::TIM15> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(feature = "stm32f100")]
+impl InternalTrigger<pac::TIM3> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(feature = "stm32f100", feature = "medium"))]
+impl InternalTrigger<pac::TIM4> for pac::TIM2 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(feature = "stm32f100")]
+impl InternalTrigger<pac::TIM2> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(feature = "stm32f100", feature = "stm32f100"))]
+impl InternalTrigger<pac::TIM15> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(feature = "stm32f100", feature = "medium"))]
+impl InternalTrigger<pac::TIM4> for pac::TIM3 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    feature = "medium",
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(feature = "stm32f100", feature = "medium"))]
+impl InternalTrigger<pac::TIM2> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(feature = "stm32f100", feature = "medium"))]
+impl InternalTrigger<pac::TIM3> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(feature = "stm32f100", feature = "medium", feature = "stm32f100"))]
+impl InternalTrigger<pac::TIM15> for pac::TIM4 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
+#[cfg(all(feature = "stm32f100", any(feature = "high", feature = "connectivity")))]
+impl InternalTrigger<pac::TIM2> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(feature = "stm32f100", any(feature = "high", feature = "connectivity")))]
+impl InternalTrigger<pac::TIM3> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(
+    feature = "stm32f100",
+    any(feature = "high", feature = "connectivity"),
+    feature = "medium"
+))]
+impl InternalTrigger<pac::TIM4> for pac::TIM5 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    all(feature = "stm32f103", feature = "high"),
+    any(feature = "stm32f100", feature = "stm32f103", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM1> for pac::TIM8 {
+    const SOURCE: TriggerSource = TriggerSource::Itr0;
+}
+#[cfg(all(feature = "stm32f103", feature = "high"))]
+impl InternalTrigger<pac::TIM2> for pac::TIM8 {
+    const SOURCE: TriggerSource = TriggerSource::Itr1;
+}
+#[cfg(all(all(feature = "stm32f103", feature = "high"), feature = "medium"))]
+impl InternalTrigger<pac::TIM4> for pac::TIM8 {
+    const SOURCE: TriggerSource = TriggerSource::Itr2;
+}
+#[cfg(all(
+    all(feature = "stm32f103", feature = "high"),
+    any(feature = "high", feature = "connectivity")
+))]
+impl InternalTrigger<pac::TIM5> for pac::TIM8 {
+    const SOURCE: TriggerSource = TriggerSource::Itr3;
+}
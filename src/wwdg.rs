@@ -0,0 +1,168 @@
+//! # Window Watchdog
+//!
+//! The WWDG is clocked from `PCLK1 / 4096`, divided again by the prescaler. Its 7-bit down
+//! counter resets the MCU when it rolls over from `0x40` to `0x3F`, or when it is refreshed while
+//! the counter is still above the window value.
+//!
+//! The early wakeup interrupt (EWI) fires when the counter reaches `0x40`, one tick before the
+//! reset. It can be used to save some state, for example into the backup registers.
+//!
+//! ```rust
+//! let mut wdg = dp.WWDG.constrain(&mut mcu);
+//! let mut ewi = wdg.get_early_wakeup_handler();
+//! all_it::WWDG_CB.set(&mut mcu, move || {
+//!     if ewi.is_interrupted() {
+//!         bkp.write_data_register_low(0, 0xDEAD);
+//!     }
+//! });
+//! wdg.listen_early_wakeup();
+//! // Reset after 50 ms, feeding is only allowed in the last 20 ms.
+//! wdg.start(50.millis(), 20.millis());
+//! loop {
+//!     if wdg.try_feed().is_ok() {
+//!         // ...
+//!     }
+//! }
+//! ```
+
+use crate::{
+    Mcu, Steal,
+    pac::{DBGMCU as DBG, WWDG, wwdg::cfr::WDGTB},
+    time::Hertz,
+};
+use core::convert::Infallible;
+use fugit::MicrosDurationU32;
+
+/// T6 bit of the counter, the reset is generated when it is cleared.
+const COUNTER_T6: u8 = 0x40;
+const COUNTER_MAX: u8 = 0x7F;
+
+pub trait WwdgInit {
+    fn constrain(self, mcu: &mut Mcu) -> WindowWatchdog;
+}
+
+impl WwdgInit for WWDG {
+    fn constrain(self, mcu: &mut Mcu) -> WindowWatchdog {
+        WindowWatchdog::new(self, mcu)
+    }
+}
+
+pub struct WindowWatchdog {
+    wwdg: WWDG,
+    clk: Hertz,
+    counter: u8,
+}
+
+impl WindowWatchdog {
+    pub fn new(wwdg: WWDG, mcu: &mut Mcu) -> Self {
+        mcu.rcc.enable(&wwdg);
+        mcu.rcc.reset(&wwdg);
+        Self {
+            clk: mcu.rcc.get_clock(&wwdg),
+            wwdg,
+            counter: COUNTER_MAX,
+        }
+    }
+
+    /// Enable the watchdog. It can't be stopped until the next reset.
+    ///
+    /// - `timeout`: the reset happens if it is not fed within this time.
+    /// - `window`: feeding is only allowed in the last `window` before the timeout,
+    ///   feeding earlier resets the MCU immediately.
+    ///   Set it to the same value as `timeout` to disable the window.
+    pub fn start(&mut self, timeout: MicrosDurationU32, window: MicrosDurationU32) {
+        let (psc, counter, window) =
+            compute_counter_window(self.clk.raw(), timeout.to_micros(), window.to_micros());
+        self.counter = counter;
+
+        self.wwdg
+            .cfr()
+            .modify(|_, w| w.wdgtb().variant(psc).w().set(window));
+        self.wwdg
+            .cr()
+            .write(|w| w.t().set(counter).wdga().set_bit());
+    }
+
+    /// Reload the counter. If the window is not open, the MCU will be reset.
+    #[inline]
+    pub fn feed(&mut self) {
+        self.wwdg.cr().write(|w| w.t().set(self.counter));
+    }
+
+    /// Reload the counter only if it is inside the window.
+    #[inline]
+    pub fn try_feed(&mut self) -> nb::Result<(), Infallible> {
+        if self.is_window_open() {
+            self.feed();
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Whether it's allowed to feed now.
+    #[inline]
+    pub fn is_window_open(&self) -> bool {
+        self.wwdg.cr().read().t().bits() <= self.wwdg.cfr().read().w().bits()
+    }
+
+    /// Enable the early wakeup interrupt.
+    /// It can only be disabled by a reset.
+    ///
+    /// Note, you will also have to enable the WWDG interrupt in the NVIC.
+    pub fn listen_early_wakeup(&mut self) {
+        self.wwdg.sr().write(|w| w.ewif().finished());
+        self.wwdg.cfr().modify(|_, w| w.ewi().enable());
+    }
+
+    pub fn get_early_wakeup_handler(&self) -> WwdgEarlyWakeup {
+        WwdgEarlyWakeup {
+            wwdg: unsafe { self.wwdg.steal() },
+        }
+    }
+
+    /// Stopping the watchdog in debug mode prevents it from resetting the MCU at a breakpoint.
+    pub fn stop_in_debug(&mut self, state: bool) {
+        let dbg = unsafe { DBG::steal() };
+        dbg.cr().modify(|_, w| w.dbg_wwdg_stop().bit(state));
+    }
+}
+
+/// Can be used in the WWDG interrupt callback.
+pub struct WwdgEarlyWakeup {
+    wwdg: WWDG,
+}
+
+impl WwdgEarlyWakeup {
+    /// Check and clear the early wakeup flag.
+    #[inline]
+    pub fn is_interrupted(&mut self) -> bool {
+        if self.wwdg.sr().read().ewif().is_pending() {
+            self.wwdg.sr().write(|w| w.ewif().finished());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// Utilities ------------------------------------------------------------------
+
+/// Returns (prescaler, counter, window)
+fn compute_counter_window(pclk1: u32, timeout_us: u32, window_us: u32) -> (WDGTB, u8, u8) {
+    const PRESCALERS: [WDGTB; 4] = [WDGTB::Div1, WDGTB::Div2, WDGTB::Div4, WDGTB::Div8];
+    let max_ticks = (COUNTER_MAX - COUNTER_T6 + 1) as u64;
+
+    for (i, psc) in PRESCALERS.into_iter().enumerate() {
+        let div = 1_000_000 * (4096_u64 << i);
+        let ticks = timeout_us as u64 * pclk1 as u64 / div;
+        if ticks <= max_ticks {
+            assert!(ticks > 0, "WWDG timeout is too short");
+            let window_ticks = (window_us as u64 * pclk1 as u64 / div).clamp(1, ticks);
+            let counter = COUNTER_T6 - 1 + ticks as u8;
+            let window = COUNTER_T6 - 1 + window_ticks as u8;
+            return (psc, counter, window);
+        }
+    }
+    panic!("WWDG timeout is too long");
+}